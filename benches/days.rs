@@ -0,0 +1,261 @@
+// Times `parse`, `part_1` and `part_2` of every day against its input.
+//
+// cargo bench                                   # writes bench_output.txt
+// cargo bench -- day11                          # only the days containing "day11"
+// cargo bench -- --baseline old.txt             # flags anything slower than old.txt
+// cargo bench -- --baseline old.txt --threshold 5
+//
+// The output is tab separated: day, step, median, min and max time per iteration in ns.
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2022::{
+    day13, input,
+    runner::YEAR,
+    solution::{Day, Params, Solution, DAYS},
+};
+
+const OUTPUT: &str = "bench_output.txt";
+const SAMPLE_COUNT: usize = 20;
+const SAMPLE_TIME: Duration = Duration::from_millis(20);
+// Anything slower than that is only sampled a few times
+const SLOW: Duration = Duration::from_millis(500);
+const SLOW_SAMPLE_COUNT: usize = 3;
+
+fn main() {
+    let mut bench = Bench::from_args();
+    for day in DAYS {
+        bench.day(day);
+    }
+    day13_trees(&mut bench);
+    bench.finish();
+}
+
+// How day13 used to solve part 2, by building every packet and sorting them
+fn day13_trees(bench: &mut Bench) {
+    let Some(input) = bench.input("day13") else {
+        return;
+    };
+    let Ok(pairs) = day13::Day13::parse(&input) else {
+        return;
+    };
+    let packets = || {
+        pairs
+            .iter()
+            .flat_map(|(left, right)| [left, right])
+            .map(|packet| packet.parse().unwrap())
+            .collect::<Vec<day13::Packet>>()
+    };
+    let trees = packets();
+    bench.record(
+        "day13",
+        "part_2_sorted_trees",
+        measure(|| {
+            let dividers: [day13::Packet; 2] = ["[[2]]".parse().unwrap(), "[[6]]".parse().unwrap()];
+            let mut packets = trees.clone();
+            packets.extend(dividers.clone());
+            packets.sort();
+            let position = |divider| packets.iter().position(|p| *p == divider).unwrap() + 1;
+            let [divider_2, divider_6] = dividers;
+            position(divider_2) * position(divider_6)
+        }),
+    );
+    bench.record("day13", "parse_trees", measure(packets));
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Measurement {
+    /// Time per iteration in ns
+    median: u128,
+    min: u128,
+    max: u128,
+}
+
+struct Bench {
+    filter: Option<String>,
+    output: String,
+    baseline: Option<HashMap<(String, String), Measurement>>,
+    /// In percent
+    threshold: f64,
+    results: Vec<(String, &'static str, Measurement)>,
+    regressions: usize,
+}
+
+impl Bench {
+    fn from_args() -> Self {
+        let mut bench = Self {
+            filter: None,
+            output: OUTPUT.into(),
+            baseline: None,
+            threshold: 10.0,
+            results: vec![],
+            regressions: 0,
+        };
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--baseline" => {
+                    let path = args.next().expect("--baseline needs a path");
+                    let baseline = std::fs::read_to_string(&path)
+                        .unwrap_or_else(|err| panic!("Failed to read {path}: {err}"));
+                    bench.baseline = Some(read_results(&baseline));
+                }
+                "--threshold" => {
+                    bench.threshold = args
+                        .next()
+                        .and_then(|t| t.parse().ok())
+                        .expect("--threshold needs a percentage");
+                }
+                "--output" => bench.output = args.next().expect("--output needs a path"),
+                // passed by cargo bench
+                "--bench" => {}
+                filter if !filter.starts_with('-') => bench.filter = Some(filter.into()),
+                _ => {}
+            }
+        }
+        bench
+    }
+
+    fn day(&mut self, day: &Day) {
+        let Some(input) = self.input(day.name) else {
+            return;
+        };
+        let data = match (day.parse)(&input) {
+            Ok(data) => data,
+            Err(err) => {
+                println!("{}: skipped, {err}", day.name);
+                return;
+            }
+        };
+
+        self.record(day.name, "parse", measure(|| (day.parse)(&input)));
+        let params = Params::default();
+        self.record(
+            day.name,
+            "part_1",
+            measure(|| (day.part_1)(&*data, &params)),
+        );
+        self.record(
+            day.name,
+            "part_2",
+            measure(|| (day.part_2)(&*data, &params)),
+        );
+    }
+
+    // None when the day is filtered out or its input is missing
+    fn input(&self, day: &str) -> Option<String> {
+        if matches!(&self.filter, Some(filter) if !day.contains(filter.as_str())) {
+            return None;
+        }
+        let path = format!("inputs/{YEAR}/{}.txt", &day[3..]);
+        let input = input::read(&path);
+        if input.is_err() {
+            println!("{day}: skipped, {path} not found");
+        }
+        input.ok()
+    }
+
+    fn record(&mut self, day: &str, step: &'static str, measurement: Measurement) {
+        let mut line = format!(
+            "{day} {step:<6} {:>12} (min {}, max {})",
+            format_ns(measurement.median),
+            format_ns(measurement.min),
+            format_ns(measurement.max),
+        );
+        let previous = self
+            .baseline
+            .as_ref()
+            .and_then(|baseline| baseline.get(&(day.to_string(), step.to_string())));
+        if let Some(previous) = previous {
+            let change = (measurement.median as f64 / previous.median.max(1) as f64 - 1.0) * 100.0;
+            write!(line, " {change:+.1}%").unwrap();
+            // the min is less noisy, both must be slower to count as a regression
+            if change > self.threshold && measurement.min > previous.min {
+                line.push_str(" REGRESSION");
+                self.regressions += 1;
+            }
+        }
+        println!("{line}");
+        self.results.push((day.to_string(), step, measurement));
+    }
+
+    fn finish(self) {
+        let mut output = String::from("day\tstep\tmedian_ns\tmin_ns\tmax_ns\n");
+        for (day, step, m) in &self.results {
+            writeln!(output, "{day}\t{step}\t{}\t{}\t{}", m.median, m.min, m.max).unwrap();
+        }
+        std::fs::write(&self.output, output)
+            .unwrap_or_else(|err| panic!("Failed to write {}: {err}", self.output));
+        println!("results written to {}", self.output);
+
+        if self.regressions > 0 {
+            println!(
+                "{} regression(s) over the {}% threshold",
+                self.regressions, self.threshold
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Runs `f` in batches long enough to be timed precisely and keeps the time per iteration
+fn measure<R>(mut f: impl FnMut() -> R) -> Measurement {
+    // warm up, also gives an estimate of the batch size
+    let start = Instant::now();
+    black_box(f());
+    let once = start.elapsed();
+
+    let iterations = (SAMPLE_TIME.as_nanos() / once.as_nanos().max(1)).max(1);
+    let sample_count = if once > SLOW {
+        SLOW_SAMPLE_COUNT
+    } else {
+        SAMPLE_COUNT
+    };
+    let mut samples: Vec<u128> = (0..sample_count)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            start.elapsed().as_nanos() / iterations
+        })
+        .collect();
+    samples.sort_unstable();
+    Measurement {
+        median: samples[samples.len() / 2],
+        min: samples[0],
+        max: samples[samples.len() - 1],
+    }
+}
+
+fn read_results(text: &str) -> HashMap<(String, String), Measurement> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [day, step, median, min, max] = fields[..] else {
+                return None;
+            };
+            let measurement = Measurement {
+                median: median.parse().ok()?,
+                min: min.parse().ok()?,
+                max: max.parse().ok()?,
+            };
+            Some(((day.to_string(), step.to_string()), measurement))
+        })
+        .collect()
+}
+
+fn format_ns(ns: u128) -> String {
+    match ns {
+        0..=999 => format!("{ns} ns"),
+        1_000..=999_999 => format!("{:.1} µs", ns as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1} ms", ns as f64 / 1e6),
+        _ => format!("{:.2} s", ns as f64 / 1e9),
+    }
+}
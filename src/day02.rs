@@ -1,15 +1,21 @@
-use serde_scan::scan;
 use strum::EnumString;
 
-type Data = Vec<(String, String)>;
+use crate::{
+    parse::ParseError,
+    parse_error,
+    solution::{Answer, Solution},
+    try_scan,
+};
+
+pub type Data = Vec<(Hand, Move)>;
 
 #[derive(Debug, PartialEq, EnumString, Copy, Clone)]
-enum Hand {
-    #[strum(serialize = "A", serialize = "X")]
+pub enum Hand {
+    #[strum(serialize = "A")]
     Rock,
-    #[strum(serialize = "B", serialize = "Y")]
+    #[strum(serialize = "B")]
     Paper,
-    #[strum(serialize = "C", serialize = "Z")]
+    #[strum(serialize = "C")]
     Scissors,
 }
 
@@ -23,8 +29,9 @@ impl Hand {
     }
 }
 
+/// The second column, a hand in part 1 and how the round must end in part 2
 #[derive(Debug, PartialEq, EnumString, Copy, Clone)]
-enum Move {
+pub enum Move {
     #[strum(serialize = "X")]
     Lose,
     #[strum(serialize = "Y")]
@@ -33,40 +40,67 @@ enum Move {
     Win,
 }
 
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|line| scan!("{} {}" <- line).expect("Failed to parse input"))
-        .collect()
+impl Move {
+    /// How part 1 reads the column, X is rock, Y paper and Z scissors
+    fn hand(&self) -> Hand {
+        match self {
+            Move::Lose => Hand::Rock,
+            Move::Draw => Hand::Paper,
+            Move::Win => Hand::Scissors,
+        }
+    }
 }
 
-pub fn part_1(input: &Data) -> usize {
-    input
-        .iter()
-        .map(|(a, b)| (a.parse::<Hand>().unwrap(), b.parse::<Hand>().unwrap()))
-        .map(|(opponent, you)| you.score() + score_game((opponent, you)))
-        .sum()
-}
+pub struct Day02;
 
-pub fn part_2(input: &Data) -> usize {
-    use Hand::*;
-    use Move::*;
-    input
-        .iter()
-        .map(|(a, b)| (a.parse::<Hand>().unwrap(), b.parse::<Move>().unwrap()))
-        .map(|(opponent, end_move)| {
-            let you = match (opponent, end_move) {
-                (Rock, Lose) => Scissors,
-                (Rock, Win) => Paper,
-                (Paper, Lose) => Rock,
-                (Paper, Win) => Scissors,
-                (Scissors, Lose) => Paper,
-                (Scissors, Win) => Rock,
-                (_, Draw) => opponent,
-            };
-            you.score() + score_game((opponent, you))
-        })
-        .sum()
+impl Solution for Day02 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let (a, b): (String, String) = try_scan!(input, "{} {}" <- line)?;
+                let hand = a
+                    .parse()
+                    .map_err(|_| parse_error!(input, line, "A, B or C"))?;
+                let second = b
+                    .parse()
+                    .map_err(|_| parse_error!(input, &line[a.len() + 1..], "X, Y or Z"))?;
+                Ok((hand, second))
+            })
+            .collect()
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .iter()
+            .map(|(opponent, second)| (*opponent, second.hand()))
+            .map(|(opponent, you)| you.score() + score_game((opponent, you)))
+            .sum::<usize>()
+            .into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        use Hand::*;
+        use Move::*;
+        Ok(input
+            .iter()
+            .map(|(opponent, end_move)| {
+                let you = match (*opponent, *end_move) {
+                    (Rock, Lose) => Scissors,
+                    (Rock, Win) => Paper,
+                    (Paper, Lose) => Rock,
+                    (Paper, Win) => Scissors,
+                    (Scissors, Lose) => Paper,
+                    (Scissors, Win) => Rock,
+                    (_, Draw) => *opponent,
+                };
+                you.score() + score_game((*opponent, you))
+            })
+            .sum::<usize>()
+            .into())
+    }
 }
 
 fn score_game((opponent, you): (Hand, Hand)) -> usize {
@@ -83,25 +117,19 @@ fn score_game((opponent, you): (Hand, Hand)) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        A Y
-        B X
-        C Z
-    "};
-
-    #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 15);
-    }
+    use super::{Day02, Hand, Move};
+    use crate::solution::Solution;
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 12);
+    pub fn parse() {
+        let expected = vec![(Hand::Rock, Move::Draw), (Hand::Scissors, Move::Win)];
+        assert_eq!(Day02::parse("A Y\nC Z\n").unwrap(), expected);
+        let err = Day02::parse("A Y\nX Y\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 1, "A, B or C")
+        );
+        let err = Day02::parse("A C\n").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (3, "X, Y or Z"));
     }
 }
@@ -1,34 +1,76 @@
-type Data = Vec<Vec<char>>;
+use crate::{
+    parse::ParseError,
+    parse_error,
+    solution::{Answer, Solution},
+};
 
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|l| l.parse::<String>().unwrap().chars().collect())
-        .collect()
-}
+pub type Data = Vec<Vec<char>>;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        let lines: Vec<&str> = input.lines().collect();
+        let sacks = lines
+            .iter()
+            .map(|line| parse_sack(input, line))
+            .collect::<Result<Data, _>>()?;
+        if !sacks.len().is_multiple_of(3) {
+            let end = &input[input.len()..];
+            return Err(parse_error!(input, end, "a number of sacks divisible by 3"));
+        }
+        for (group, lines) in sacks.chunks(3).zip(lines.chunks(3)) {
+            let [a, b, c] = group else { unreachable!() };
+            if !a.iter().any(|ac| b.contains(ac) && c.contains(ac)) {
+                return Err(parse_error!(input, lines[0], "3 sacks with a common item"));
+            }
+        }
+        Ok(sacks)
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .iter()
+            .map(|sack| sack.split_at(sack.len() / 2))
+            .map(|(a, b)| {
+                let ac = a.iter().find(|ac| b.contains(ac)).unwrap();
+                priority(*ac)
+            })
+            .sum::<u32>()
+            .into())
+    }
 
-pub fn part_1(input: &Data) -> usize {
-    input
-        .iter()
-        .map(|sack| sack.split_at(sack.len() / 2))
-        .map(|(a, b)| {
-            let ac = a.iter().find(|ac| b.contains(ac)).unwrap();
-            priority(*ac)
-        })
-        .sum::<u32>() as usize
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .chunks(3)
+            .flat_map(|group| {
+                let [a, b, c] = group else { unreachable!() };
+                a.iter().find(|ac| b.contains(ac) && c.contains(ac))
+            })
+            .map(|ac| priority(*ac))
+            .sum::<u32>()
+            .into())
+    }
 }
 
-pub fn part_2(input: &Data) -> usize {
-    input
-        .chunks(3)
-        .flat_map(|group| {
-            let [a, b, c] = group else {
-                unreachable!()
-            };
-            a.iter().find(|ac| b.contains(ac) && c.contains(ac))
-        })
-        .map(|ac| priority(*ac))
-        .sum::<u32>() as usize
+fn parse_sack(input: &str, line: &str) -> Result<Vec<char>, ParseError> {
+    if let Some((i, _)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+        return Err(parse_error!(input, &line[i..], "a letter"));
+    }
+    if !line.len().is_multiple_of(2) {
+        return Err(parse_error!(input, line, "an even number of items"));
+    }
+    let (a, b) = line.split_at(line.len() / 2);
+    if !a.chars().any(|ac| b.contains(ac)) {
+        return Err(parse_error!(
+            input,
+            line,
+            "2 compartments with a common item"
+        ));
+    }
+    Ok(line.chars().collect())
 }
 
 fn priority(c: char) -> u32 {
@@ -43,28 +85,28 @@ fn priority(c: char) -> u32 {
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        vJrwpWtwJgWrhcsFMMfFFhFp
-        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-        PmmdzqPrVvPwwTWBwg
-        wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-        ttgJtRGJQctTZtZT
-        CrZsJsPPZsGzwwsLwLmpwMDw
-    "};
+    use super::Day03;
+    use crate::solution::Solution;
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 157);
-    }
+    pub fn parse() {
+        let group =
+            "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg\n";
+        assert_eq!(Day03::parse(group).unwrap().len(), 3);
 
-    #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 70);
+        let err = Day03::parse(&group[..group.len() - 19]).unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (3, "a number of sacks divisible by 3")
+        );
+        let err = Day03::parse("abcab\n").unwrap_err();
+        assert_eq!(err.expected, "an even number of items");
+        let err = Day03::parse("abcd\n").unwrap_err();
+        assert_eq!(err.expected, "2 compartments with a common item");
+        let err = Day03::parse("aa\nbb\ncc\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (1, "3 sacks with a common item")
+        );
     }
 }
@@ -1,57 +1,131 @@
-use hashbrown::HashSet;
+use std::io::{self, BufReader, Read};
 
-type Data = Vec<char>;
+use anyhow::Context;
 
-pub fn parse(input: &str) -> Data {
-    input.chars().collect()
+use crate::{
+    parse::ParseError,
+    solution::{Answer, Solution},
+};
+
+pub type Data = Vec<u8>;
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        Ok(input.trim_end().as_bytes().to_vec())
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        let marker = markers(input.iter().copied(), 4).next();
+        Ok(marker.context("no start-of-packet marker")?.into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        let marker = markers(input.iter().copied(), 14).next();
+        Ok(marker.context("no start-of-message marker")?.into())
+    }
+}
+
+/// Finds the windows of distinct letters in a stream, one byte at a time and without allocating.
+/// Anything that isn't a lowercase letter can't be part of a marker.
+pub struct MarkerDetector {
+    window: usize,
+    counts: [usize; 26],
+    /// Number of letters that appear more than once in the window
+    duplicates: usize,
+    /// Ring buffer of the letters in the window
+    recent: Vec<u8>,
+    len: usize,
+    position: usize,
 }
 
-pub fn part_1(input: &Data) -> usize {
-    find_marker(input, 4)
+impl MarkerDetector {
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "the window can't be empty");
+        Self {
+            window,
+            counts: [0; 26],
+            duplicates: 0,
+            recent: vec![0; window],
+            len: 0,
+            position: 0,
+        }
+    }
+
+    /// Returns the number of bytes read so far when they end with a marker
+    pub fn push(&mut self, byte: u8) -> Option<usize> {
+        let slot = self.position % self.window;
+        self.position += 1;
+        if !byte.is_ascii_lowercase() {
+            self.counts = [0; 26];
+            self.duplicates = 0;
+            self.len = 0;
+            return None;
+        }
+
+        if self.len == self.window {
+            // the slot still holds the letter that just left the window
+            let old = (self.recent[slot] - b'a') as usize;
+            self.counts[old] -= 1;
+            if self.counts[old] == 1 {
+                self.duplicates -= 1;
+            }
+        } else {
+            self.len += 1;
+        }
+        let letter = (byte - b'a') as usize;
+        self.counts[letter] += 1;
+        if self.counts[letter] == 2 {
+            self.duplicates += 1;
+        }
+        self.recent[slot] = byte;
+
+        (self.len == self.window && self.duplicates == 0).then_some(self.position)
+    }
 }
 
-pub fn part_2(input: &Data) -> usize {
-    find_marker(input, 14)
+/// The end position of every marker, counted in bytes from the start of the stream
+pub fn markers(bytes: impl IntoIterator<Item = u8>, window: usize) -> impl Iterator<Item = usize> {
+    let mut detector = MarkerDetector::new(window);
+    bytes
+        .into_iter()
+        .filter_map(move |byte| detector.push(byte))
 }
 
-fn find_marker(data: &Data, size: usize) -> usize {
-    data.windows(size)
-        .position(|window| window.iter().collect::<HashSet<_>>().len() == size)
-        .unwrap()
-        + size
+/// Same as `markers` but for files or sockets too big to be loaded in memory
+pub fn read_markers(reader: impl Read, window: usize) -> impl Iterator<Item = io::Result<usize>> {
+    let mut detector = MarkerDetector::new(window);
+    BufReader::new(reader)
+        .bytes()
+        .filter_map(move |byte| match byte {
+            Ok(byte) => detector.push(byte).map(Ok),
+            Err(err) => Some(Err(err)),
+        })
 }
 
 #[cfg(test)]
 mod tests {
-    #[test]
-    pub fn part_1() {
-        let inputs = vec![
-            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7),
-            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5),
-            ("nppdvjthqldpwncqszvftbrmjlhg", 6),
-            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10),
-            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11),
-        ];
-        for (input, expected) in inputs {
-            let parsed = super::parse(input);
-            let result = super::part_1(&parsed);
-            assert_eq!(result, expected);
-        }
-    }
+    use super::Day06;
+    use crate::solution::Solution;
 
     #[test]
-    pub fn part_2() {
-        let inputs = vec![
-            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 19),
-            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 23),
-            ("nppdvjthqldpwncqszvftbrmjlhg", 23),
-            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 29),
-            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 26),
-        ];
-        for (input, expected) in inputs {
-            let parsed = super::parse(input);
-            let result = super::part_2(&parsed);
-            assert_eq!(result, expected);
-        }
+    pub fn markers() {
+        let all: Vec<_> = super::markers(*b"abcabcd", 3).collect();
+        assert_eq!(all, vec![3, 4, 5, 6, 7]);
+        let all: Vec<_> = super::markers(*b"aabbcdd", 3).collect();
+        assert_eq!(all, vec![6]);
+        // a line break isn't a letter, it ends the window
+        let all: Vec<_> = super::markers(*b"ab\ncd\n", 2).collect();
+        assert_eq!(all, vec![2, 5]);
+
+        let reader = std::io::Cursor::new("mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+        let first = super::read_markers(reader, 14).next().unwrap().unwrap();
+        assert_eq!(first, 19);
+
+        let input = Day06::parse("aaaaaa\n").unwrap();
+        assert!(Day06::part_1(&input).is_err());
     }
 }
@@ -1,114 +1,298 @@
-use hashbrown::HashMap;
-use serde_scan::scan;
+use std::fmt;
 
-type Data = HashMap<Vec<String>, FsEntry>;
+use anyhow::Context;
 
-#[derive(Default, Debug)]
-pub struct FsEntry {
-    dirs: Vec<String>,
-    files: Vec<File>,
-    size: usize,
+use crate::{
+    parse::ParseError,
+    parse_error,
+    runner::Options,
+    solution::{Answer, Solution},
+};
+
+pub type Data = FileSystem;
+
+pub type DirId = usize;
+
+#[derive(Debug, Clone)]
+pub struct Dir {
+    pub name: String,
+    /// None for the root
+    pub parent: Option<DirId>,
+    pub dirs: Vec<DirId>,
+    pub files: Vec<File>,
+    /// Total size of the files in this directory and all its subdirectories
+    pub size: usize,
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct File {
-    name: String,
-    size: usize,
+    pub name: String,
+    pub size: usize,
 }
 
-pub fn parse(input: &str) -> Data {
-    let mut fs: HashMap<Vec<String>, FsEntry> = HashMap::new();
-    let mut cwd: Vec<String> = vec![];
-    for line in input.lines() {
-        match line {
-            _ if line.starts_with("$ cd") => {
-                let dir_name: &str = scan!("$ cd {}" <- line).unwrap();
-                match dir_name {
-                    "/" => cwd = vec!["/".into()],
-                    ".." => {
-                        cwd.pop();
-                    }
-                    dir => cwd.push(dir.into()),
-                }
-            }
-            _ if line.starts_with("$ ls") => {}
-            _ if line.starts_with("dir") => {
-                fs.entry(cwd.clone())
-                    .or_default()
-                    .dirs
-                    .push(line.replace("dir ", ""));
-            }
-            line => {
-                let data = line.split(' ').collect::<Vec<_>>();
-                fs.entry(cwd.clone()).or_default().files.push(File {
-                    name: data[1].into(),
-                    size: data[0].parse().unwrap(),
+/// Every directory is stored in a single vec, they refer to each other by index
+#[derive(Debug, Clone)]
+pub struct FileSystem {
+    dirs: Vec<Dir>,
+}
+
+impl FileSystem {
+    pub const ROOT: DirId = 0;
+
+    fn new() -> Self {
+        let root = Dir {
+            name: "/".into(),
+            parent: None,
+            dirs: vec![],
+            files: vec![],
+            size: 0,
+        };
+        Self { dirs: vec![root] }
+    }
+
+    /// Rebuilds the file system from the commands and their output
+    pub fn replay(input: &str) -> Result<Self, ParseError> {
+        let mut fs = Self::new();
+        let mut cwd = Self::ROOT;
+        // The output lines only make sense right after a `$ ls`
+        let mut listing = false;
+        for line in input.lines() {
+            if let Some(dir_name) = line.strip_prefix("$ cd ") {
+                listing = false;
+                cwd = match dir_name {
+                    "/" => Self::ROOT,
+                    // like a shell, the parent of the root is the root
+                    ".." => fs.dirs[cwd].parent.unwrap_or(Self::ROOT),
+                    dir => fs.child(cwd, dir),
+                };
+            } else if line == "$ ls" {
+                listing = true;
+                // listing the same directory again replaces what we knew about it
+                fs.dirs[cwd].files.clear();
+            } else if !listing {
+                return Err(parse_error!(input, line, "`$ cd {}` or `$ ls`"));
+            } else if let Some(dir_name) = line.strip_prefix("dir ") {
+                fs.child(cwd, dir_name);
+            } else {
+                let expected = "`$ cd {}`, `$ ls`, `dir {}` or `{size} {}`";
+                let (size, name) = line
+                    .split_once(' ')
+                    .ok_or_else(|| parse_error!(input, line, expected))?;
+                let size = size
+                    .parse()
+                    .map_err(|_| parse_error!(input, line, expected))?;
+                fs.dirs[cwd].files.push(File {
+                    name: name.into(),
+                    size,
                 });
             }
         }
+        fs.compute_sizes();
+        Ok(fs)
+    }
+
+    /// Finds the subdirectory or creates it if it's the first time we see it
+    fn child(&mut self, parent: DirId, name: &str) -> DirId {
+        if let Some(id) = self.dirs[parent]
+            .dirs
+            .iter()
+            .find(|id| self.dirs[**id].name == name)
+        {
+            return *id;
+        }
+        let id = self.dirs.len();
+        self.dirs.push(Dir {
+            name: name.into(),
+            parent: Some(parent),
+            dirs: vec![],
+            files: vec![],
+            size: 0,
+        });
+        self.dirs[parent].dirs.push(id);
+        id
+    }
+
+    // A directory is always created after its parent so going backward
+    // visits the children before their parent
+    fn compute_sizes(&mut self) {
+        for dir in &mut self.dirs {
+            dir.size = dir.files.iter().map(|f| f.size).sum();
+        }
+        for id in (0..self.dirs.len()).rev() {
+            if let Some(parent) = self.dirs[id].parent {
+                self.dirs[parent].size += self.dirs[id].size;
+            }
+        }
+    }
+
+    pub fn dir(&self, id: DirId) -> &Dir {
+        &self.dirs[id]
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = &Dir> {
+        self.dirs.iter()
+    }
+
+    pub fn dirs_by_size(&self, predicate: impl Fn(usize) -> bool) -> impl Iterator<Item = &Dir> {
+        self.dirs().filter(move |dir| predicate(dir.size))
+    }
+
+    /// Absolute path of the directory, `/` for the root
+    pub fn path(&self, id: DirId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.dirs[current].parent {
+            names.push(self.dirs[current].name.as_str());
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Finds a directory from its absolute path
+    pub fn lookup(&self, path: &str) -> Option<DirId> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(Self::ROOT, |id, name| {
+                self.dirs[id]
+                    .dirs
+                    .iter()
+                    .copied()
+                    .find(|child| self.dirs[*child].name == name)
+            })
+    }
+
+    /// The `n` biggest files with their path, biggest first
+    pub fn largest_files(&self, n: usize) -> Vec<(String, usize)> {
+        let mut files: Vec<_> = (0..self.dirs.len())
+            .flat_map(|id| self.dirs[id].files.iter().map(move |file| (id, file)))
+            .collect();
+        files.sort_by_key(|(_, file)| std::cmp::Reverse(file.size));
+        files
+            .into_iter()
+            .take(n)
+            .map(|(id, file)| {
+                let dir = self.path(id);
+                let separator = if dir.ends_with('/') { "" } else { "/" };
+                (format!("{dir}{separator}{}", file.name), file.size)
+            })
+            .collect()
     }
 
-    let keys: Vec<_> = fs.keys().cloned().collect();
-    for key in keys {
-        let size = dir_size(&fs, &key);
-        fs.entry(key).and_modify(|entry| entry.size = size);
+    /// Like `du -d max_depth`, the size of every directory up to that depth, children first.
+    /// The root is at depth 0.
+    pub fn du(&self, max_depth: usize) -> Vec<(String, usize)> {
+        let mut totals = vec![];
+        self.du_visit(Self::ROOT, 0, max_depth, &mut totals);
+        totals
     }
 
-    // print_dir(&fs, vec!["/".into()], 0);
+    fn du_visit(
+        &self,
+        id: DirId,
+        depth: usize,
+        max_depth: usize,
+        totals: &mut Vec<(String, usize)>,
+    ) {
+        if depth > max_depth {
+            return;
+        }
+        for child in &self.dirs[id].dirs {
+            self.du_visit(*child, depth + 1, max_depth, totals);
+        }
+        totals.push((self.path(id), self.dirs[id].size));
+    }
 
-    fs
+    /// The smallest directory that frees enough space once deleted
+    pub fn dir_to_delete(&self, disk: Disk) -> Option<&Dir> {
+        let unused = disk.capacity.saturating_sub(self.dir(Self::ROOT).size);
+        let missing = disk.required.saturating_sub(unused);
+        self.dirs_by_size(|size| size >= missing)
+            .min_by_key(|dir| dir.size)
+    }
+
+    fn fmt_dir(&self, f: &mut fmt::Formatter<'_>, id: DirId, prefix: &str) -> fmt::Result {
+        let dir = &self.dirs[id];
+        let count = dir.dirs.len() + dir.files.len();
+        for (i, child) in dir.dirs.iter().enumerate() {
+            let last = i + 1 == count;
+            let child_dir = &self.dirs[*child];
+            let branch = if last { "└── " } else { "├── " };
+            writeln!(
+                f,
+                "{prefix}{branch}{} (dir, size={})",
+                child_dir.name, child_dir.size
+            )?;
+            let indent = if last { "    " } else { "│   " };
+            self.fmt_dir(f, *child, &format!("{prefix}{indent}"))?;
+        }
+        for (i, file) in dir.files.iter().enumerate() {
+            let branch = if dir.dirs.len() + i + 1 == count {
+                "└── "
+            } else {
+                "├── "
+            };
+            writeln!(
+                f,
+                "{prefix}{branch}{} (file, size={})",
+                file.name, file.size
+            )?;
+        }
+        Ok(())
+    }
 }
 
-pub fn part_1(fs: &Data) -> usize {
-    fs.iter()
-        .map(|(_k, entry)| entry.size)
-        .filter(|size| *size <= 100000)
-        .sum()
+/// Formatted like the `tree` command
+impl fmt::Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "/ (dir, size={})", self.dir(Self::ROOT).size)?;
+        self.fmt_dir(f, Self::ROOT, "")
+    }
 }
 
-pub fn part_2(fs: &Data) -> usize {
-    let total_used = fs.get(&vec!["/".into()]).unwrap().size;
-    let unused = 70_000_000 - total_used;
-    let space_needed = 30_000_000;
-    fs.values()
-        .map(|v| v.size)
-        .filter(|size| unused + size >= space_needed)
-        .min()
-        .unwrap()
+#[derive(Debug, Clone, Copy)]
+pub struct Disk {
+    pub capacity: usize,
+    /// Free space needed for the update
+    pub required: usize,
 }
 
-fn dir_size(fs: &HashMap<Vec<String>, FsEntry>, entry: &Vec<String>) -> usize {
-    let entry_data = fs.get(entry).unwrap();
-    let mut total = 0;
-    for dir in &entry_data.dirs {
-        let mut dir_entry = entry.clone();
-        dir_entry.push(dir.clone());
-        total += dir_size(fs, &dir_entry);
+impl Default for Disk {
+    fn default() -> Self {
+        Self {
+            capacity: 70_000_000,
+            required: 30_000_000,
+        }
     }
-    total += entry_data.files.iter().fold(0, |acc, f| acc + f.size);
-    total
 }
 
-#[allow(unused)]
-fn print_dir(fs: &HashMap<Vec<String>, FsEntry>, entry: Vec<String>, level: usize) {
-    let entry_data = fs.get(&entry).unwrap();
-    println!("- {} (dir) {}", entry.last().unwrap(), entry_data.size);
-    for dir in entry_data.dirs.clone() {
-        print_indent(level + 1);
-        let mut dir_entry = entry.clone();
-        dir_entry.push(dir);
-        print_dir(fs, dir_entry, level + 1);
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        FileSystem::replay(input)
     }
-    for file in entry_data.files.clone() {
-        print_indent(level + 1);
-        println!("- {} (file, size={})", file.name, file.size);
+
+    fn extras(fs: &Data, options: &Options) {
+        // `cargo run -- --tree` shows the file system
+        if options.tree {
+            print!("{fs}");
+        }
     }
-}
 
-#[allow(unused)]
-fn print_indent(size: usize) {
-    for _ in 0..size * 2 {
-        print!(" ");
+    fn part_1(fs: &Data) -> anyhow::Result<Answer> {
+        Ok(fs
+            .dirs_by_size(|size| size <= 100000)
+            .map(|dir| dir.size)
+            .sum::<usize>()
+            .into())
+    }
+
+    fn part_2(fs: &Data) -> anyhow::Result<Answer> {
+        let dir = fs.dir_to_delete(Disk::default());
+        Ok(dir.context("no directory frees enough space")?.size.into())
     }
 }
 
@@ -116,43 +300,106 @@ fn print_indent(size: usize) {
 mod tests {
     use indoc::indoc;
 
-    const INPUTS: &str = indoc! {"
-        $ cd /
-        $ ls
-        dir a
-        14848514 b.txt
-        8504156 c.dat
-        dir d
-        $ cd a
-        $ ls
-        dir e
-        29116 f
-        2557 g
-        62596 h.lst
-        $ cd e
-        $ ls
-        584 i
-        $ cd ..
-        $ cd ..
-        $ cd d
-        $ ls
-        4060174 j
-        8033020 d.log
-        5626152 d.ext
-        7214296 k
-    "};
+    use super::Day07;
+    use crate::{example, solution::Solution};
+
+    #[test]
+    pub fn replay() {
+        use super::FileSystem;
+
+        let input = indoc! {"
+            $ cd a
+            $ ls
+            10 x
+            dir b
+            $ cd b
+            $ ls
+            5 y
+            $ cd /
+            $ cd a
+            $ ls
+            10 x
+            dir b
+            $ cd ..
+            $ cd ..
+            $ ls
+            dir a
+            1 z
+        "};
+        let fs = FileSystem::replay(input).unwrap();
+        let sizes: Vec<_> = fs.dirs().map(|dir| (dir.name.as_str(), dir.size)).collect();
+        assert_eq!(sizes, vec![("/", 16), ("a", 15), ("b", 5)]);
+        assert_eq!(fs.dir(2).parent, Some(1));
+
+        let err = FileSystem::replay("$ cd /\n10 x\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (2, "`$ cd {}` or `$ ls`")
+        );
+    }
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 95437);
+    pub fn queries() {
+        use super::{Disk, FileSystem};
+
+        let fs = Day07::parse(&example::input("day07")).unwrap();
+        let e = fs.lookup("/a/e").unwrap();
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(fs.dir(e).size, 584);
+        assert_eq!(fs.lookup("/a/x"), None);
+        assert_eq!(fs.lookup("/"), Some(FileSystem::ROOT));
+
+        let largest = fs.largest_files(2);
+        assert_eq!(
+            largest,
+            vec![("/b.txt".into(), 14848514), ("/c.dat".into(), 8504156)]
+        );
+        let du = fs.du(1);
+        assert_eq!(
+            du,
+            vec![
+                ("/a".into(), 94853),
+                ("/d".into(), 24933642),
+                ("/".into(), 48381165)
+            ]
+        );
+
+        let small_disk = Disk {
+            capacity: 50_000_000,
+            required: 1_700_000,
+        };
+        assert_eq!(fs.dir_to_delete(small_disk).unwrap().name, "a");
+        let tiny_disk = Disk {
+            capacity: 1_000,
+            required: 100_000_000,
+        };
+        assert!(fs.dir_to_delete(tiny_disk).is_none());
     }
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 24933642);
+    pub fn tree() {
+        let input = indoc! {"
+            $ cd /
+            $ ls
+            dir a
+            1 b
+            $ cd a
+            $ ls
+            dir c
+            2 d
+            $ cd c
+            $ ls
+            3 e
+        "};
+        let fs = Day07::parse(input).unwrap();
+        let expected = indoc! {"
+            / (dir, size=6)
+            ├── a (dir, size=5)
+            │   ├── c (dir, size=3)
+            │   │   └── e (file, size=3)
+            │   └── d (file, size=2)
+            └── b (file, size=1)
+        "};
+        assert_eq!(fs.to_string(), expected);
     }
 }
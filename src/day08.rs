@@ -1,85 +1,97 @@
-type Data = Vec<Vec<u32>>;
+use anyhow::Context;
+use colored::Color;
 
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect()
-}
+use crate::{
+    export::Export,
+    grid::{Grid, Point, NEIGHBOURS_4},
+    parse::ParseError,
+    runner::Options,
+    solution::{Answer, Solution},
+    visualize::Pixel,
+};
 
-pub fn part_1(input: &Data) -> usize {
-    let dimensions = (input[0].len(), input.len());
-    let (width, height) = dimensions;
-    let mut count = 0;
-    for y in 1..height - 1 {
-        for x in 1..width - 1 {
-            if check_vis(input, &dimensions, (x, y), (-1, 0))
-                || check_vis(input, &dimensions, (x, y), (1, 0))
-                || check_vis(input, &dimensions, (x, y), (0, -1))
-                || check_vis(input, &dimensions, (x, y), (0, 1))
-            {
-                count += 1;
-                continue;
-            }
-        }
+pub type Data = Grid<u32>;
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        Grid::parse(module_path!(), input, "a digit", |c| c.to_digit(10))
     }
-    count + (((width - 2) + (height - 2)) * 2 + 4)
-}
 
-pub fn part_2(input: &Data) -> usize {
-    let dimensions = (input[0].len(), input.len());
-    let (width, height) = dimensions;
-    let mut max_score = 0;
-    for y in 0..height {
-        for x in 0..width {
-            let score = scenic_score(input, &dimensions, (x, y), (0, -1))
-                * scenic_score(input, &dimensions, (x, y), (-1, 0))
-                * scenic_score(input, &dimensions, (x, y), (0, 1))
-                * scenic_score(input, &dimensions, (x, y), (1, 0));
-            max_score = max_score.max(score);
+    fn extras(input: &Data, options: &Options) {
+        if let Some(export) = Export::from_options(options, module_path!()) {
+            export.png(&heat_map(input));
         }
     }
-    max_score
-}
 
-fn check_vis(
-    input: &Data,
-    (width, height): &(usize, usize),
-    (x, y): (usize, usize),
-    dir: (i32, i32),
-) -> bool {
-    let curr_tree = input[y][x];
-    let (mut x, mut y) = (x as i32, y as i32);
-    loop {
-        x += dir.0;
-        y += dir.1;
-        if x == -1 || x >= *width as i32 || y == -1 || y >= *height as i32 {
-            break;
-        }
-        if input[y as usize][x as usize] >= curr_tree {
-            return false;
-        }
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .iter()
+            .filter(|(point, _)| is_visible(input, *point))
+            .count()
+            .into())
     }
-    true
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .iter()
+            .map(|(point, _)| total_scenic_score(input, point))
+            .max()
+            .context("there are no trees")?
+            .into())
+    }
+}
+
+/// The trees colored by scenic score, from blue to red. The ones hidden from outside are darker.
+pub fn heat_map(input: &Data) -> Vec<(Point, Pixel)> {
+    let scores: Vec<_> = input
+        .iter()
+        .map(|(point, tree)| (point, *tree, total_scenic_score(input, point)))
+        .collect();
+    // the best scores are far above the others, the square root keeps the rest visible
+    let max = scores.iter().map(|(_, _, score)| *score).max().unwrap_or(0);
+    let max = (max as f64).sqrt().max(1.0);
+    scores
+        .into_iter()
+        .map(|(point, tree, score)| {
+            let heat = (score as f64).sqrt() / max;
+            let brightness = if is_visible(input, point) { 1.0 } else { 0.5 };
+            let color = Color::TrueColor {
+                r: (255.0 * heat * brightness) as u8,
+                g: (60.0 * brightness) as u8,
+                b: (255.0 * (1.0 - heat) * brightness) as u8,
+            };
+            let symbol = char::from_digit(tree, 10).unwrap_or('?');
+            (point, Pixel::new(symbol, color))
+        })
+        .collect()
+}
+
+pub fn is_visible(input: &Data, point: Point) -> bool {
+    NEIGHBOURS_4.iter().any(|dir| check_vis(input, point, *dir))
+}
+
+pub fn total_scenic_score(input: &Data, point: Point) -> usize {
+    NEIGHBOURS_4
+        .iter()
+        .map(|dir| scenic_score(input, point, *dir))
+        .product()
+}
+
+fn check_vis(input: &Data, point: Point, dir: Point) -> bool {
+    let curr_tree = input[point];
+    input.ray(point, dir).all(|(_, tree)| *tree < curr_tree)
 }
 
-fn scenic_score(
-    input: &Data,
-    (width, height): &(usize, usize),
-    (x, y): (usize, usize),
-    dir: (i32, i32),
-) -> usize {
-    let curr_tree = input[y][x];
-    let (mut x, mut y) = (x as i32, y as i32);
+fn scenic_score(input: &Data, point: Point, dir: Point) -> usize {
+    let curr_tree = input[point];
     let mut score = 0;
-    loop {
-        x += dir.0;
-        y += dir.1;
-        if x == -1 || x >= *width as i32 || y == -1 || y >= *height as i32 {
-            break;
-        }
+    for (_, tree) in input.ray(point, dir) {
         score += 1;
-        if input[y as usize][x as usize] >= curr_tree {
+        if *tree >= curr_tree {
             break;
         }
     }
@@ -88,27 +100,13 @@ fn scenic_score(
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        30373
-        25512
-        65332
-        33549
-        35390
-    "};
-
-    #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 21);
-    }
+    use super::Day08;
+    use crate::solution::{Answer, Solution};
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 8);
+    pub fn empty() {
+        let input = Day08::parse("").unwrap();
+        assert_eq!(Day08::part_1(&input).unwrap(), Answer::Int(0));
+        assert!(Day08::part_2(&input).is_err());
     }
 }
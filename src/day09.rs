@@ -1,142 +1,187 @@
-use glam::Vec2;
+use colored::Color;
 use hashbrown::HashSet;
-use serde_scan::scan;
-
-type Data = Vec<(Vec2, i32)>;
-
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|line| scan!("{} {}" <- line).expect("Failed to parse input"))
-        .map(|(dir, amount)| {
-            let dir = match dir {
-                'U' => Vec2::NEG_Y,
-                'D' => Vec2::Y,
-                'L' => Vec2::NEG_X,
-                'R' => Vec2::X,
-                _ => unreachable!(),
-            };
-            (dir, amount)
-        })
-        .collect()
+
+use crate::{
+    export::Export,
+    grid::Point,
+    parse::ParseError,
+    parse_error,
+    runner::Options,
+    solution::{Answer, Solution},
+    try_scan,
+    visualize::{Pixel, Player},
+};
+
+// (direction, number of steps)
+pub type Data = Vec<(Point, i32)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rope {
+    /// The head is the first knot
+    knots: Vec<Point>,
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let mut head = Vec2::ZERO;
-    let mut tail = Vec2::ZERO;
-    let mut tail_cache = HashSet::new();
-    for (dir, amount) in input {
-        for _ in 0..*amount {
-            head += *dir;
-            tail = follow(tail, head);
-            tail_cache.insert((tail.x as i32, tail.y as i32));
+impl Rope {
+    /// Every knot starts at (0, 0)
+    pub fn new(knot_count: usize) -> Self {
+        assert!(knot_count > 0, "a rope needs at least one knot");
+        Self {
+            knots: vec![(0, 0); knot_count],
+        }
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    /// Moves the head by one in `dir` and pulls the other knots
+    pub fn step(&mut self, dir: Point) {
+        self.knots[0].0 += dir.0;
+        self.knots[0].1 += dir.1;
+        for i in 1..self.knots.len() {
+            self.knots[i] = follow(self.knots[i], self.knots[i - 1]);
         }
     }
-    tail_cache.len()
-}
 
-pub fn part_2(input: &Data) -> usize {
-    let mut head = Vec2::ZERO;
-    let mut tail_cache = HashSet::new();
-    let mut rope = vec![Vec2::ZERO; 9];
-    for (dir, amount) in input {
-        for _ in 0..*amount {
-            head += *dir;
-            rope[0] = follow(rope[0], head);
-            for i in 1..rope.len() {
-                rope[i] = follow(rope[i], rope[i - 1]);
+    /// Applies every move one step at a time, `on_step` sees the rope after each step
+    pub fn run(&mut self, moves: &[(Point, i32)], mut on_step: impl FnMut(&Rope)) {
+        for (dir, amount) in moves {
+            for _ in 0..*amount {
+                self.step(*dir);
+                on_step(self);
             }
-            let tail = rope.last().unwrap();
-            tail_cache.insert((tail.x as i32, tail.y as i32));
         }
     }
-    tail_cache.len()
+
+    /// Every position visited by a knot, the head is 0. The starting position is included.
+    pub fn visited(&mut self, moves: &[(Point, i32)], knot: usize) -> HashSet<Point> {
+        let mut visited = HashSet::from([self.knots[knot]]);
+        self.run(moves, |rope| {
+            visited.insert(rope.knots[knot]);
+        });
+        visited
+    }
+
+    /// The knots after each step, starting with their position before the first move
+    pub fn snapshots(&mut self, moves: &[(Point, i32)]) -> Vec<Vec<Point>> {
+        let mut snapshots = vec![self.knots.clone()];
+        self.run(moves, |rope| snapshots.push(rope.knots.clone()));
+        snapshots
+    }
 }
 
-fn follow(tail: Vec2, head: Vec2) -> Vec2 {
-    let dir = head - tail;
-    if dir.length() == 2.0 {
-        tail + dir.normalize()
-    } else if dir.length() > 2.0 {
-        tail + dir.signum()
+// A knot only moves when it doesn't touch the knot in front of it anymore,
+// it then takes one step, diagonally if needed, toward that knot
+fn follow(knot: Point, leader: Point) -> Point {
+    let (dx, dy) = (leader.0 - knot.0, leader.1 - knot.1);
+    if dx.abs() <= 1 && dy.abs() <= 1 {
+        knot
     } else {
-        tail
+        (knot.0 + dx.signum(), knot.1 + dy.signum())
     }
 }
 
-#[allow(unused)]
-fn print_cache(size: i32, cache: &HashSet<(i32, i32)>) {
-    for y in -size..size {
-        for x in -size..size {
-            if (x, y) == (0, 0) {
-                print!("s");
-            } else if cache.contains(&(x, y)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let (dir, amount): (&str, i32) = try_scan!(input, "{} {}" <- line)?;
+                // the diagonal moves aren't used by the puzzle
+                let dir = match dir {
+                    "U" => (0, -1),
+                    "D" => (0, 1),
+                    "L" => (-1, 0),
+                    "R" => (1, 0),
+                    "UL" => (-1, -1),
+                    "UR" => (1, -1),
+                    "DL" => (-1, 1),
+                    "DR" => (1, 1),
+                    _ => return Err(parse_error!(input, line, "U, D, L, R, UL, UR, DL or DR")),
+                };
+                Ok((dir, amount))
+            })
+            .collect()
+    }
+
+    fn extras(input: &Data, options: &Options) {
+        if let Some(mut player) = Player::from_options(options, module_path!()) {
+            trail(input, |frame| player.show(&frame));
+        }
+        if let Some(export) = Export::from_options(options, module_path!()) {
+            let mut recorder = export.recorder(10);
+            let mut last_frame = vec![];
+            trail(input, |frame| {
+                recorder.record(&frame);
+                last_frame = frame;
+            });
+            export.png(&last_frame);
+            export.gif(&recorder);
         }
-        println!();
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(Rope::new(2).visited(input, 1).len().into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Ok(Rope::new(10).visited(input, 9).len().into())
     }
 }
 
-#[allow(unused)]
-fn print_step(size: i32, head: (i32, i32), rope: &[(i32, i32)]) {
-    for y in -size..size {
-        for x in -size..size {
-            match (x, y) {
-                (0, 0) => print!("s"),
-                pos if pos == head => print!("H"),
-                pos => match rope.iter().position(|i| i == &pos) {
-                    Some(i) => print!("{}", i + 1),
-                    None => print!("."),
-                },
-            }
+// Draws every step of part 2, the cells visited by the tail stay behind it
+pub fn trail(moves: &Data, mut on_frame: impl FnMut(Vec<(Point, Pixel)>)) {
+    let mut visited: HashSet<Point> = HashSet::from([(0, 0)]);
+    Rope::new(10).run(moves, |rope| {
+        visited.insert(*rope.knots().last().unwrap());
+        let mut frame: Vec<_> = visited
+            .iter()
+            .map(|point| (*point, Pixel::new('#', Color::BrightBlack)))
+            .collect();
+        frame.push(((0, 0), Pixel::new('s', Color::Blue)));
+        // drawn from the tail so the knots in front end up on top
+        for (i, knot) in rope.knots().iter().enumerate().rev() {
+            let pixel = match i {
+                0 => Pixel::new('H', Color::Red),
+                i => Pixel::new(char::from_digit(i as u32, 36).unwrap(), Color::Yellow),
+            };
+            frame.push((*knot, pixel));
         }
-        println!();
-    }
+        on_frame(frame);
+    });
 }
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        R 4
-        U 4
-        L 3
-        D 1
-        R 4
-        D 1
-        L 5
-        R 2
-    "};
+    use super::Day09;
+    use crate::{example, solution::Solution};
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 13);
-    }
+    pub fn rope() {
+        use super::Rope;
 
-    #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 1);
-
-        let large_input = indoc! {"
-            R 5
-            U 8
-            L 8
-            D 3
-            R 17
-            D 10
-            L 25
-            U 20
-        "};
-        let input = super::parse(large_input);
-        let result = super::part_2(&input);
-        assert_eq!(result, 36);
+        let input = Day09::parse("R 2\nUR 1\n").unwrap();
+        let snapshots = Rope::new(3).snapshots(&input);
+        assert_eq!(
+            snapshots,
+            vec![
+                vec![(0, 0), (0, 0), (0, 0)],
+                vec![(1, 0), (0, 0), (0, 0)],
+                vec![(2, 0), (1, 0), (0, 0)],
+                vec![(3, -1), (2, -1), (1, -1)],
+            ]
+        );
+
+        // the knot right behind the head moves the same way no matter how long the rope is
+        let input = Day09::parse(&example::input("day09")).unwrap();
+        let long = Rope::new(10).visited(&input, 1);
+        let short = Rope::new(2).visited(&input, 1);
+        assert_eq!(long, short);
+
+        assert!(Day09::parse("X 1\n").is_err());
     }
 }
@@ -1,249 +1,237 @@
-use serde_scan::scan;
+use std::fmt;
 
-type Data = Vec<(String, String)>;
+use crate::{
+    grid::Grid,
+    ocr,
+    parse::ParseError,
+    parse_error,
+    solution::{Answer, Solution},
+};
 
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|line| match line {
-            _ if line.starts_with("noop") => ("noop".into(), "".into()),
-            _ => scan!("{} {}" <- line).expect("Failed to parse input"),
-        })
-        .collect()
+pub type Data = Vec<Instruction>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Noop => write!(f, "noop"),
+            Instruction::Addx(value) => write!(f, "addx {value}"),
+        }
+    }
+}
+
+/// How many cycles each instruction takes to complete
+#[derive(Debug, Clone, Copy)]
+pub struct CycleCosts {
+    pub noop: usize,
+    pub addx: usize,
+}
+
+impl Default for CycleCosts {
+    fn default() -> Self {
+        Self { noop: 1, addx: 2 }
+    }
+}
+
+impl CycleCosts {
+    fn cost(&self, instruction: &Instruction) -> usize {
+        match instruction {
+            Instruction::Noop => self.noop,
+            Instruction::Addx(_) => self.addx,
+        }
+    }
+}
+
+/// Called during every cycle, the instruction hasn't finished executing yet
+pub trait CycleObserver {
+    /// `cycle` starts at 1
+    fn observe(&mut self, cycle: usize, instruction: &Instruction, register_x: i32);
 }
 
-struct Cpu {
+pub struct Cpu {
     cycle_count: usize,
     register_x: i32,
-    signal_strength: usize,
-    draw: bool,
+    costs: CycleCosts,
+}
+
+impl Default for Cpu {
+    fn default() -> Self {
+        Self::new(CycleCosts::default())
+    }
 }
 
 impl Cpu {
-    fn new(draw: bool) -> Self {
+    pub fn new(costs: CycleCosts) -> Self {
         Self {
             cycle_count: 0,
             register_x: 1,
-            signal_strength: 0,
-            draw,
+            costs,
         }
     }
 
-    fn run(&mut self, input: &Data) {
-        for (instruction, value) in input {
-            self.cycle();
-            match instruction.as_str() {
-                "noop" => {}
-                "addx" => {
-                    self.cycle();
-                    self.register_x += value.parse::<i32>().unwrap();
+    pub fn run(&mut self, program: &[Instruction], observers: &mut [&mut dyn CycleObserver]) {
+        for instruction in program {
+            for _ in 0..self.costs.cost(instruction) {
+                self.cycle_count += 1;
+                for observer in observers.iter_mut() {
+                    observer.observe(self.cycle_count, instruction, self.register_x);
                 }
-                _ => unreachable!(),
+            }
+            match instruction {
+                Instruction::Noop => {}
+                Instruction::Addx(value) => self.register_x += value,
             }
         }
     }
+}
 
-    fn cycle(&mut self) {
-        self.draw();
+/// Sums the signal strength during the `first` cycle and then `every` n cycles
+pub struct SignalStrength {
+    first: usize,
+    every: usize,
+    total: i64,
+}
 
-        self.cycle_count += 1;
-        if self.cycle_count == 20 || (self.cycle_count + 20) % 40 == 0 {
-            self.signal_strength += self.cycle_count * self.register_x as usize;
+impl Default for SignalStrength {
+    fn default() -> Self {
+        Self {
+            first: 20,
+            every: 40,
+            total: 0,
         }
     }
+}
 
-    fn draw(&mut self) {
-        if !self.draw {
-            return;
+impl CycleObserver for SignalStrength {
+    fn observe(&mut self, cycle: usize, _instruction: &Instruction, register_x: i32) {
+        if cycle >= self.first && (cycle - self.first).is_multiple_of(self.every) {
+            self.total += cycle as i64 * register_x as i64;
         }
-        if self.cycle_count % 40 == 0 {
-            println!();
+    }
+}
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+/// Draws a pixel each cycle, it's lit when the 3 pixels wide sprite is under it
+pub struct Crt {
+    screen: Grid<bool>,
+}
+
+impl Default for Crt {
+    fn default() -> Self {
+        Self {
+            screen: Grid::new(SCREEN_WIDTH, SCREEN_HEIGHT, false),
         }
-        if (self.register_x - self.cycle_count as i32 % 40).abs() <= 1 {
-            print!("#");
-        } else {
-            print!(" ");
+    }
+}
+
+impl CycleObserver for Crt {
+    fn observe(&mut self, cycle: usize, _instruction: &Instruction, register_x: i32) {
+        let x = ((cycle - 1) % SCREEN_WIDTH) as i32;
+        let y = ((cycle - 1) / SCREEN_WIDTH) as i32;
+        if let Some(pixel) = self.screen.get_mut((x, y)) {
+            *pixel = (register_x - x).abs() <= 1;
         }
     }
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let mut cpu = Cpu::new(false);
-    cpu.run(input);
-    cpu.signal_strength
+/// Records every cycle, useful to debug a program
+#[derive(Default)]
+pub struct Trace {
+    cycles: Vec<(usize, Instruction, i32)>,
+}
+
+impl CycleObserver for Trace {
+    fn observe(&mut self, cycle: usize, instruction: &Instruction, register_x: i32) {
+        self.cycles.push((cycle, *instruction, register_x));
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (cycle, instruction, register_x) in &self.cycles {
+            writeln!(
+                f,
+                "cycle {cycle:>3}: {:<10} X={register_x}",
+                instruction.to_string()
+            )?;
+        }
+        Ok(())
+    }
 }
 
-pub fn part_2(input: &Data) -> usize {
-    let mut cpu = Cpu::new(true);
-    cpu.run(input);
-    println!();
-    0
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        input
+            .lines()
+            .map(|line| match line.split_once(' ') {
+                None if line == "noop" => Ok(Instruction::Noop),
+                Some(("addx", value)) => value
+                    .parse()
+                    .map(Instruction::Addx)
+                    .map_err(|_| parse_error!(input, value, "a number")),
+                _ => Err(parse_error!(input, line, "`noop` or `addx {}`")),
+            })
+            .collect()
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        let mut signal_strength = SignalStrength::default();
+        Cpu::default().run(input, &mut [&mut signal_strength]);
+        Ok(signal_strength.total.into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        let mut crt = Crt::default();
+        Cpu::default().run(input, &mut [&mut crt]);
+        let letters = ocr::recognize(&crt.screen);
+        if !letters.contains('?') {
+            return Ok(letters.into());
+        }
+        // some letters aren't in the font, they can still be read on the screen
+        let screen = crt.screen.map(|lit| if *lit { '#' } else { '.' });
+        Ok(Answer::Lines(
+            screen.to_string().lines().map(String::from).collect(),
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use indoc::indoc;
 
-    const INPUTS: &str = indoc! {"
-        addx 15
-        addx -11
-        addx 6
-        addx -3
-        addx 5
-        addx -1
-        addx -8
-        addx 13
-        addx 4
-        noop
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx -35
-        addx 1
-        addx 24
-        addx -19
-        addx 1
-        addx 16
-        addx -11
-        noop
-        noop
-        addx 21
-        addx -15
-        noop
-        noop
-        addx -3
-        addx 9
-        addx 1
-        addx -3
-        addx 8
-        addx 1
-        addx 5
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx -36
-        noop
-        addx 1
-        addx 7
-        noop
-        noop
-        noop
-        addx 2
-        addx 6
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx 1
-        noop
-        noop
-        addx 7
-        addx 1
-        noop
-        addx -13
-        addx 13
-        addx 7
-        noop
-        addx 1
-        addx -33
-        noop
-        noop
-        noop
-        addx 2
-        noop
-        noop
-        noop
-        addx 8
-        noop
-        addx -1
-        addx 2
-        addx 1
-        noop
-        addx 17
-        addx -9
-        addx 1
-        addx 1
-        addx -3
-        addx 11
-        noop
-        noop
-        addx 1
-        noop
-        addx 1
-        noop
-        noop
-        addx -13
-        addx -19
-        addx 1
-        addx 3
-        addx 26
-        addx -30
-        addx 12
-        addx -1
-        addx 3
-        addx 1
-        noop
-        noop
-        noop
-        addx -9
-        addx 18
-        addx 1
-        addx 2
-        noop
-        noop
-        addx 9
-        noop
-        noop
-        noop
-        addx -1
-        addx 2
-        addx -37
-        addx 1
-        addx 3
-        noop
-        addx 15
-        addx -21
-        addx 22
-        addx -6
-        addx 1
-        noop
-        addx 2
-        addx 1
-        noop
-        addx -10
-        noop
-        noop
-        addx 20
-        addx 1
-        addx 2
-        addx 2
-        addx -6
-        addx -11
-        noop
-        noop
-        noop
-    "};
+    use super::Day10;
+    use crate::solution::Solution;
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 13140);
-    }
+    pub fn trace() {
+        use super::{Cpu, CycleCosts, Trace};
 
-    #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 0);
+        let input = Day10::parse("noop\naddx 3\naddx -5\n").unwrap();
+        let mut trace = Trace::default();
+        Cpu::new(CycleCosts::default()).run(&input, &mut [&mut trace]);
+        let expected = indoc! {"
+            cycle   1: noop       X=1
+            cycle   2: addx 3     X=1
+            cycle   3: addx 3     X=1
+            cycle   4: addx -5    X=4
+            cycle   5: addx -5    X=4
+        "};
+        assert_eq!(trace.to_string(), expected);
+
+        let mut trace = Trace::default();
+        let costs = CycleCosts { noop: 2, addx: 3 };
+        Cpu::new(costs).run(&input, &mut [&mut trace]);
+        assert_eq!(trace.to_string().lines().count(), 8);
     }
 }
@@ -1,205 +1,538 @@
-use serde_scan::scan;
+use std::{fmt, ops::Rem, str::Lines};
 
-type Data = Vec<Monkey>;
+use anyhow::{bail, Context};
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
 
-pub fn parse(input: &str) -> Data {
-    input.split("\n\n").map(parse_monkey).collect()
-}
+use crate::{
+    parse::ParseError,
+    parse_error,
+    solution::{Answer, Solution},
+    try_scan,
+};
+
+pub type Data = Vec<Monkey>;
 
 #[derive(Default, Debug, Clone)]
 pub struct Monkey {
     index: usize,
     items: Vec<usize>,
-    operation: (String, String),
+    operation: Expr,
     test_value: usize,
     test_true: usize,
     test_false: usize,
-    inspect_count: usize,
 }
 
-fn parse_monkey(block: &str) -> Monkey {
+fn parse_monkey(input: &str, block: &str) -> Result<Monkey, ParseError> {
     let mut monkey = Monkey::default();
     let mut lines = block.lines();
 
-    let line = lines.next().unwrap();
-    monkey.index = scan!("Monkey {}:" <- line).unwrap();
+    let line = next_line(input, block, &mut lines)?;
+    monkey.index = try_scan!(input, "Monkey {}:" <- line)?;
 
-    let line = lines.next().unwrap().trim();
-    let items: &str = scan!("Starting items: {}" <- line).unwrap();
+    let line = next_line(input, block, &mut lines)?;
+    let items: &str = try_scan!(input, "Starting items: {}" <- line)?;
     monkey.items = items
         .split(',')
-        .map(|x| x.trim().parse().unwrap())
-        .collect();
+        .map(str::trim)
+        .map(|x| {
+            x.parse()
+                .map_err(|_| parse_error!(input, x, "a worry level"))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let line = next_line(input, block, &mut lines)?;
+    let operation = line
+        .strip_prefix("Operation: new = ")
+        .ok_or_else(|| parse_error!(input, line, "`Operation: new = {}`"))?;
+    monkey.operation = Expr::parse(input, operation)?;
+
+    let line = next_line(input, block, &mut lines)?;
+    monkey.test_value = try_scan!(input, "Test: divisible by {}" <- line)?;
+    if monkey.test_value == 0 {
+        return Err(parse_error!(input, line, "a divisor above 0"));
+    }
 
-    let line = lines.next().unwrap().trim();
-    monkey.operation = scan!("Operation: new = old {} {}" <- line).unwrap();
+    let line = next_line(input, block, &mut lines)?;
+    monkey.test_true = try_scan!(input, "If true: throw to monkey {}" <- line)?;
 
-    let line = lines.next().unwrap().trim();
-    monkey.test_value =
-        scan!("Test: divisible by {}" <- line).unwrap_or_else(|_| panic!("Failed to parse {line}"));
+    let line = next_line(input, block, &mut lines)?;
+    monkey.test_false = try_scan!(input, "If false: throw to monkey {}" <- line)?;
 
-    let line = lines.next().unwrap().trim();
-    monkey.test_true = scan!("If true: throw to monkey {}" <- line).unwrap();
+    Ok(monkey)
+}
 
-    let line = lines.next().unwrap().trim();
-    monkey.test_false = scan!("If false: throw to monkey {}" <- line).unwrap();
+fn next_line<'a>(
+    input: &str,
+    block: &'a str,
+    lines: &mut Lines<'a>,
+) -> Result<&'a str, ParseError> {
+    lines
+        .next()
+        .map(str::trim)
+        .ok_or_else(|| parse_error!(input, &block[block.len()..], "another line"))
+}
 
-    monkey
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Expr {
+    #[default]
+    Old,
+    Literal(usize),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let mut monkeys = input.clone();
-    for _ in 0..20 {
-        for i in 0..input.len() {
-            let monkey = monkeys[i].clone();
+impl Expr {
+    /// `text` must be a slice of `input`
+    pub fn parse(input: &str, text: &str) -> Result<Self, ParseError> {
+        let mut parser = ExprParser { input, rest: text };
+        let expr = parser.sum()?;
+        parser.skip_whitespace();
+        if !parser.rest.is_empty() {
+            return Err(parse_error!(input, parser.rest, "an operator"));
+        }
+        Ok(expr)
+    }
 
-            if monkey.items.is_empty() {
-                continue;
-            }
+    /// True when it only uses + and *, then it gives the same result modulo any number
+    pub fn is_modular(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Literal(_) => true,
+            Expr::Add(a, b) | Expr::Mul(a, b) => a.is_modular() && b.is_modular(),
+            Expr::Sub(..) | Expr::Div(..) => false,
+        }
+    }
 
-            for item in monkey.items {
-                let mut worry_level = item;
+    /// None when the result doesn't fit in `W`, a worry level can't be negative, or when dividing
+    /// by zero
+    pub fn eval<W: Worry>(&self, old: &W) -> Option<W> {
+        match self {
+            Expr::Old => Some(old.clone()),
+            Expr::Literal(value) => Some(W::from(*value)),
+            Expr::Add(a, b) => a.eval(old)?.checked_add(&b.eval(old)?),
+            Expr::Sub(a, b) => a.eval(old)?.checked_sub(&b.eval(old)?),
+            Expr::Mul(a, b) => a.eval(old)?.checked_mul(&b.eval(old)?),
+            Expr::Div(a, b) => a.eval(old)?.checked_div(&b.eval(old)?),
+        }
+    }
+}
 
-                let (operator, value) = &monkey.operation;
-                if value != "old" {
-                    let value = value.parse::<usize>().unwrap();
-                    match operator.as_str() {
-                        "*" => worry_level *= value,
-                        "+" => worry_level += value,
-                        _ => unreachable!(),
-                    }
-                } else {
-                    match operator.as_str() {
-                        "*" => worry_level *= worry_level,
-                        "+" => worry_level += worry_level,
-                        _ => unreachable!(),
-                    }
-                }
+// Recursive descent parser, * and / have precedence over + and -
+struct ExprParser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
 
-                worry_level /= 3;
+impl<'a> ExprParser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
 
-                let throw_index = if worry_level % monkey.test_value == 0 {
-                    monkey.test_true
-                } else {
-                    monkey.test_false
-                };
-                monkeys[throw_index].items.push(worry_level);
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
             }
+            None => false,
+        }
+    }
 
-            monkeys[i].inspect_count += monkeys[i].items.len();
-            monkeys[i].items.clear();
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.product()?;
+        loop {
+            if self.eat('+') {
+                expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+            } else if self.eat('-') {
+                expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+            } else {
+                return Ok(expr);
+            }
         }
     }
 
-    monkeys.sort_by_key(|m| m.inspect_count);
-    monkeys
-        .iter()
-        .rev()
-        .take(2)
-        .map(|m| m.inspect_count)
-        .product()
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.operand()?;
+        loop {
+            if self.eat('*') {
+                expr = Expr::Mul(Box::new(expr), Box::new(self.operand()?));
+            } else if self.eat('/') {
+                expr = Expr::Div(Box::new(expr), Box::new(self.operand()?));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<Expr, ParseError> {
+        if self.eat('(') {
+            let expr = self.sum()?;
+            if !self.eat(')') {
+                return Err(parse_error!(self.input, self.rest, "`)`"));
+            }
+            return Ok(expr);
+        }
+        if let Some(rest) = self.rest.strip_prefix("old") {
+            self.rest = rest;
+            return Ok(Expr::Old);
+        }
+        let len = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let (literal, rest) = self.rest.split_at(len);
+        let value = literal
+            .parse()
+            .map_err(|_| parse_error!(self.input, self.rest, "`old`, a number or `(`"))?;
+        self.rest = rest;
+        Ok(Expr::Literal(value))
+    }
+}
+
+/// A worry level, `usize` is enough when the levels are kept in check, `BigUint` otherwise
+pub trait Worry:
+    Clone
+    + PartialEq
+    + fmt::Debug
+    + From<usize>
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + Rem<Output = Self>
+{
 }
 
-pub fn part_2(input: &Data) -> usize {
-    let mut monkeys = input.clone();
-    let modulo: usize = monkeys.iter().map(|m| m.test_value).product();
+impl<W> Worry for W where
+    W: Clone
+        + PartialEq
+        + fmt::Debug
+        + From<usize>
+        + CheckedAdd
+        + CheckedSub
+        + CheckedMul
+        + CheckedDiv
+        + Rem<Output = Self>
+{
+}
 
-    for _ in 0..10000 {
-        for i in 0..input.len() {
-            let monkey = monkeys[i].clone();
+/// What happens to the worry level after a monkey inspected an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relief {
+    Divide(usize),
+    /// Keeps the level modulo the LCM of the tests, every test still gives the same result.
+    /// Only holds when the operations use + and *, the simulation refuses it otherwise.
+    ModuloLcm,
+    /// The levels grow without bound, only use it with big integers
+    None,
+}
 
-            if monkey.items.is_empty() {
-                continue;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Throw<W> {
+    /// Starts at 1
+    pub round: usize,
+    pub from: usize,
+    pub to: usize,
+    pub worry: W,
+}
+
+#[derive(Debug, Clone)]
+pub struct Stats<W> {
+    /// Number of items inspected by each monkey
+    pub inspections: Vec<usize>,
+    /// Every throw of each item, indexed like the starting items in monkey order.
+    /// Only filled when the simulation records histories.
+    pub histories: Vec<Vec<Throw<W>>>,
+}
 
-            for item in monkey.items {
-                let mut worry_level = item;
+impl<W> Stats<W> {
+    /// Product of the two highest inspection counts
+    pub fn monkey_business(&self) -> usize {
+        let mut inspections = self.inspections.clone();
+        inspections.sort_unstable_by(|a, b| b.cmp(a));
+        inspections.iter().take(2).product()
+    }
+}
 
-                let (operator, value) = &monkey.operation;
-                if value != "old" {
-                    let value = value.parse::<usize>().unwrap();
-                    match operator.as_str() {
-                        "*" => worry_level *= value,
-                        "+" => worry_level += value,
-                        _ => unreachable!(),
+pub struct MonkeySim<'a> {
+    monkeys: &'a [Monkey],
+    rounds: usize,
+    relief: Relief,
+    record_histories: bool,
+}
+
+impl<'a> MonkeySim<'a> {
+    pub fn new(monkeys: &'a [Monkey], rounds: usize, relief: Relief) -> Self {
+        Self {
+            monkeys,
+            rounds,
+            relief,
+            record_histories: false,
+        }
+    }
+
+    pub fn record_histories(mut self) -> Self {
+        self.record_histories = true;
+        self
+    }
+
+    pub fn run<W: Worry>(&self) -> anyhow::Result<Stats<W>> {
+        self.run_with(|_, _, _| {})
+    }
+
+    /// `hook` is called after each round with the items held by every monkey as (item, worry).
+    /// Fails when an operation gives a worry level that `W` can't hold.
+    pub fn run_with<W: Worry>(
+        &self,
+        mut hook: impl FnMut(usize, &[Vec<(usize, W)>], &Stats<W>),
+    ) -> anyhow::Result<Stats<W>> {
+        // only the modulo relief needs the LCM
+        let mut modulo = None;
+        if self.relief == Relief::ModuloLcm {
+            if let Some(monkey) = self.monkeys.iter().find(|m| !m.operation.is_modular()) {
+                bail!(
+                    "monkey {} uses - or /, its levels can't be kept modulo the LCM of the tests",
+                    monkey.index
+                );
+            }
+            let lcm = self.lcm::<W>();
+            modulo = Some(lcm.context("the LCM of the tests is too big for the worry levels")?);
+        }
+        let zero = W::from(0);
+
+        let mut item_count = 0;
+        let mut held: Vec<Vec<(usize, W)>> = self
+            .monkeys
+            .iter()
+            .map(|m| {
+                m.items
+                    .iter()
+                    .map(|worry| {
+                        item_count += 1;
+                        (item_count - 1, W::from(*worry))
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut stats = Stats {
+            inspections: vec![0; self.monkeys.len()],
+            histories: vec![vec![]; if self.record_histories { item_count } else { 0 }],
+        };
+
+        for round in 1..=self.rounds {
+            for (i, monkey) in self.monkeys.iter().enumerate() {
+                let items = std::mem::take(&mut held[i]);
+                stats.inspections[i] += items.len();
+                for (item, worry) in items {
+                    let mut worry = monkey.operation.eval(&worry).with_context(|| {
+                        format!("monkey {i} can't compute a new worry level from {worry:?}")
+                    })?;
+                    match self.relief {
+                        Relief::Divide(divisor) => {
+                            worry = worry
+                                .checked_div(&W::from(divisor))
+                                .context("the relief can't divide by 0")?;
+                        }
+                        Relief::ModuloLcm | Relief::None => {}
+                    }
+                    if let Some(modulo) = &modulo {
+                        worry = worry % modulo.clone();
                     }
-                } else {
-                    match operator.as_str() {
-                        "*" => worry_level *= worry_level,
-                        "+" => worry_level += worry_level,
-                        _ => unreachable!(),
+                    let to = if worry.clone() % W::from(monkey.test_value) == zero {
+                        monkey.test_true
+                    } else {
+                        monkey.test_false
+                    };
+                    if self.record_histories {
+                        stats.histories[item].push(Throw {
+                            round,
+                            from: i,
+                            to,
+                            worry: worry.clone(),
+                        });
                     }
+                    held[to].push((item, worry));
                 }
+            }
+            hook(round, &held, &stats);
+        }
+        Ok(stats)
+    }
 
-                worry_level %= modulo;
+    /// None when it doesn't fit in `W`
+    fn lcm<W: Worry>(&self) -> Option<W> {
+        self.monkeys.iter().try_fold(W::from(1), |lcm, monkey| {
+            let value = W::from(monkey.test_value);
+            let divisor = gcd(lcm.clone(), value.clone());
+            lcm.checked_div(&divisor)?.checked_mul(&value)
+        })
+    }
+}
 
-                let throw_index = if worry_level % monkey.test_value == 0 {
-                    monkey.test_true
-                } else {
-                    monkey.test_false
-                };
-                monkeys[throw_index].items.push(worry_level);
-            }
+fn gcd<W: Worry>(a: W, b: W) -> W {
+    if b == W::from(0) {
+        a
+    } else {
+        gcd(b.clone(), a % b)
+    }
+}
 
-            monkeys[i].inspect_count += monkeys[i].items.len();
-            monkeys[i].items.clear();
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        let blocks: Vec<&str> = input.split("\n\n").collect();
+        let monkeys = blocks
+            .iter()
+            .map(|block| parse_monkey(input, block))
+            .collect::<Result<Data, _>>()?;
+        for (block, monkey) in blocks.iter().zip(&monkeys) {
+            // the last two lines are the throws
+            for (line, to) in block
+                .lines()
+                .skip(4)
+                .zip([monkey.test_true, monkey.test_false])
+            {
+                if to >= monkeys.len() {
+                    let target = line.rsplit(' ').next().unwrap_or(line);
+                    let expected = format!("a monkey below {}", monkeys.len());
+                    return Err(parse_error!(input, target, expected));
+                }
+            }
         }
+        Ok(monkeys)
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(MonkeySim::new(input, 20, Relief::Divide(3))
+            .run::<usize>()?
+            .monkey_business()
+            .into())
     }
 
-    monkeys.sort_by_key(|m| m.inspect_count);
-    monkeys
-        .iter()
-        .rev()
-        .take(2)
-        .map(|m| m.inspect_count)
-        .product()
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Ok(MonkeySim::new(input, 10000, Relief::ModuloLcm)
+            .run::<usize>()?
+            .monkey_business()
+            .into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        Monkey 0:
-            Starting items: 79, 98
-            Operation: new = old * 19
-            Test: divisible by 23
-                If true: throw to monkey 2
-                If false: throw to monkey 3
-
-        Monkey 1:
-            Starting items: 54, 65, 75, 74
-            Operation: new = old + 6
-            Test: divisible by 19
-                If true: throw to monkey 2
-                If false: throw to monkey 0
-
-        Monkey 2:
-            Starting items: 79, 60, 97
-            Operation: new = old * old
-            Test: divisible by 13
-                If true: throw to monkey 1
-                If false: throw to monkey 3
-
-        Monkey 3:
-            Starting items: 74
-            Operation: new = old + 3
-            Test: divisible by 17
-                If true: throw to monkey 0
-                If false: throw to monkey 1
-    "};
+    use super::Day11;
+    use crate::{example, solution::Solution};
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 10605);
+    pub fn operation() {
+        use super::Expr;
+
+        let input = "(old + 2) * old - 10 / 2";
+        let expr = Expr::parse(input, input).unwrap();
+        assert_eq!(expr.eval(&3), Some(10));
+        // worry levels can't be negative
+        let input = "old - 5";
+        assert_eq!(Expr::parse(input, input).unwrap().eval(&3), None);
+        let input = "old / (old - 3)";
+        assert_eq!(Expr::parse(input, input).unwrap().eval(&3), None);
+
+        let input = "old * (2 +";
+        let err = Expr::parse(input, input).unwrap_err();
+        assert_eq!(
+            (err.column, err.expected.as_str()),
+            (11, "`old`, a number or `(`")
+        );
+        let input = "old old";
+        let err = Expr::parse(input, input).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (5, "an operator"));
     }
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 2713310158);
+    pub fn parse() {
+        let input = example::input("day11");
+        let err = Day11::parse(&input.replace("monkey 3", "monkey 4")).unwrap_err();
+        assert_eq!((err.line, err.column), (6, 35));
+        assert_eq!(
+            (err.text.as_str(), err.expected.as_str()),
+            ("4", "a monkey below 4")
+        );
+        let err = Day11::parse(&input.replace("by 23", "by 0")).unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (4, "a divisor above 0"));
+    }
+
+    #[test]
+    pub fn simulation() {
+        use num_bigint::BigUint;
+
+        use super::{Expr, MonkeySim, Relief, Throw};
+
+        let input = Day11::parse(&example::input("day11")).unwrap();
+        let mut after_first_round = vec![];
+        let stats = MonkeySim::new(&input, 1, Relief::Divide(3))
+            .record_histories()
+            .run_with::<usize>(|_, held, _| {
+                after_first_round = held
+                    .iter()
+                    .map(|items| items.iter().map(|(_, worry)| *worry).collect::<Vec<_>>())
+                    .collect();
+            })
+            .unwrap();
+        assert_eq!(
+            after_first_round,
+            vec![
+                vec![20, 23, 27, 26],
+                vec![2080, 25, 167, 207, 401, 1046],
+                vec![],
+                vec![],
+            ]
+        );
+        assert_eq!(stats.inspections, vec![2, 4, 3, 5]);
+        let first_item = vec![
+            Throw {
+                round: 1,
+                from: 0,
+                to: 3,
+                worry: 500,
+            },
+            Throw {
+                round: 1,
+                from: 3,
+                to: 1,
+                worry: 167,
+            },
+        ];
+        assert_eq!(stats.histories[0], first_item);
+
+        let stats = MonkeySim::new(&input, 20, Relief::None)
+            .run::<BigUint>()
+            .unwrap();
+        assert_eq!(stats.inspections, vec![99, 97, 8, 103]);
+
+        let mut monkeys = input.clone();
+        let operation = "old * 2 - 1";
+        monkeys[0].operation = Expr::parse(operation, operation).unwrap();
+        assert!(!monkeys[0].operation.is_modular());
+        assert!(MonkeySim::new(&monkeys, 1, Relief::ModuloLcm)
+            .run::<usize>()
+            .is_err());
+
+        // the LCM of the tests doesn't fit in 64 bits, it's only needed by the modulo relief
+        let mut monkeys = input.clone();
+        for (monkey, test_value) in monkeys.iter_mut().zip([4294967291, 4294967279, 4294967231]) {
+            monkey.test_value = test_value;
+        }
+        assert!(MonkeySim::new(&monkeys, 20, Relief::Divide(3))
+            .run::<usize>()
+            .is_ok());
+        assert!(MonkeySim::new(&monkeys, 1, Relief::ModuloLcm)
+            .run::<usize>()
+            .is_err());
+        assert!(MonkeySim::new(&monkeys, 1, Relief::ModuloLcm)
+            .run::<BigUint>()
+            .is_ok());
     }
 }
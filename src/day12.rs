@@ -1,103 +1,118 @@
-use std::collections::BinaryHeap;
+use anyhow::Context;
+use colored::Color;
 
-use hashbrown::{HashMap, HashSet};
+use crate::{
+    export::Export,
+    grid::{Grid, Point},
+    parse::ParseError,
+    parse_error,
+    runner::Options,
+    search::{self, Path},
+    solution::{Answer, Solution},
+    visualize::Pixel,
+};
 
 // (map, start, end)
-type Data = (HashMap<(i32, i32), i32>, (i32, i32), (i32, i32));
+pub type Data = (Grid<i32>, Point, Point);
 
-pub fn parse(input: &str) -> Data {
-    let chars: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+fn climb_up(map: &Grid<i32>, point: Point) -> impl Iterator<Item = Point> + '_ {
+    let height = map[point];
+    map.neighbours_4(point)
+        .filter(move |(_, n_height)| **n_height <= height + 1)
+        .map(|(n, _)| n)
+}
 
-    let mut map = HashMap::new();
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    for (y, chars) in chars.iter().enumerate() {
-        for (x, c) in chars.iter().enumerate() {
-            let point = (x as i32, y as i32);
-            let val = match c {
-                'S' => {
-                    start = point;
-                    'a'
-                }
-                'E' => {
-                    end = point;
-                    'z'
-                }
-                c => *c,
+// Same as `climb_up` but going backward, from the top to the bottom
+fn climb_down(map: &Grid<i32>, point: Point) -> impl Iterator<Item = Point> + '_ {
+    let height = map[point];
+    map.neighbours_4(point)
+        .filter(move |(_, n_height)| height <= **n_height + 1)
+        .map(|(n, _)| n)
+}
+
+pub fn shortest_path(map: &Grid<i32>, start: Point, end: Point) -> Option<Path<Point>> {
+    search::bfs([start], |p| climb_up(map, *p), |p| *p == end)
+}
+
+/// The heights from dark to light green with the shortest path in red
+pub fn path_map((map, start, end): &Data) -> Vec<(Point, Pixel)> {
+    let mut frame: Vec<_> = map
+        .iter()
+        .map(|(point, height)| {
+            let shade = (40 + height * 8) as u8;
+            let color = Color::TrueColor {
+                r: shade / 3,
+                g: shade,
+                b: shade / 3,
             };
-            map.insert(point, val as i32 - 'a' as i32);
-        }
+            (point, Pixel::new((b'a' + *height as u8) as char, color))
+        })
+        .collect();
+    if let Some(path) = shortest_path(map, *start, *end) {
+        frame.extend(
+            path.nodes
+                .into_iter()
+                .map(|p| (p, Pixel::new('#', Color::Red))),
+        );
     }
-    (map, start, end)
+    frame
 }
 
-// Djikstra algorithm
-fn shortest_path(
-    map: &HashMap<(i32, i32), i32>,
-    start: (i32, i32),
-    end: (i32, i32),
-) -> Option<i32> {
-    let mut visited = HashSet::new();
-    // This is a max heap, but we want a min heap, so steps should be negative
-    let mut heap = BinaryHeap::new();
-    heap.push((0, start));
-    while let Some((steps, (x, y))) = heap.pop() {
-        if !visited.insert((x, y)) {
-            continue;
-        }
-        if (x, y) == end {
-            return Some(-steps);
-        }
-        let curr_height = map.get(&(x, y)).unwrap();
-        let neighbours = [(0, 1), (0, -1), (1, 0), (-1, 0)];
-        for (n_x, n_y) in neighbours {
-            let n = (x + n_x, y + n_y);
-            let Some(n_height) = map.get(&n) else {
-                continue;
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        let chars = Grid::parse(module_path!(), input, "a height, S or E", |c| {
+            matches!(c, 'a'..='z' | 'S' | 'E').then_some(c)
+        })?;
+        let end_of_input = &input[input.len()..];
+        let start = chars
+            .position(|c| *c == 'S')
+            .ok_or_else(|| parse_error!(input, end_of_input, "a start position S"))?;
+        let end = chars
+            .position(|c| *c == 'E')
+            .ok_or_else(|| parse_error!(input, end_of_input, "an end position E"))?;
+        let map = chars.map(|c| {
+            let c = match c {
+                'S' => 'a',
+                'E' => 'z',
+                c => *c,
             };
-            if *n_height <= curr_height + 1 {
-                heap.push((steps - 1, n));
-            }
+            c as i32 - 'a' as i32
+        });
+        Ok((map, start, end))
+    }
+
+    fn extras(input: &Data, options: &Options) {
+        if let Some(export) = Export::from_options(options, module_path!()) {
+            export.png(&path_map(input));
         }
     }
-    None
-}
 
-pub fn part_1((map, start, end): &Data) -> usize {
-    shortest_path(map, *start, *end).unwrap() as usize
-}
+    fn part_1((map, start, end): &Data) -> anyhow::Result<Answer> {
+        let path = shortest_path(map, *start, *end).context("E can't be reached from S")?;
+        Ok(path.cost.into())
+    }
 
-pub fn part_2((map, _start, end): &Data) -> usize {
-    map.iter()
-        .filter(|(_, value)| **value == 0)
-        .flat_map(|(point, _)| shortest_path(map, *point, *end))
-        .min()
-        .unwrap() as usize
+    fn part_2((map, _start, end): &Data) -> anyhow::Result<Answer> {
+        // The closest 'a' from the end is also the start of the shortest path to the end
+        let path = search::bfs([*end], |p| climb_down(map, *p), |p| map[*p] == 0)
+            .context("E can't be reached from any square of height a")?;
+        Ok(path.cost.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        Sabqponm
-        abcryxxl
-        accszExk
-        acctuvwj
-        abdefghi
-    "};
-
-    #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 31);
-    }
+    use super::Day12;
+    use crate::solution::Solution;
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 29);
+    pub fn unreachable() {
+        let input = Day12::parse("SazE\n").unwrap();
+        assert!(Day12::part_1(&input).is_err());
+        assert!(Day12::part_2(&input).is_err());
     }
 }
@@ -1,164 +1,418 @@
-use std::collections::VecDeque;
-use std::fmt::Write;
+use std::{cmp::Ordering, fmt, str::FromStr};
 
-type Data = Vec<(Packet, Packet)>;
+use crate::{
+    parse::ParseError,
+    parse_error,
+    solution::{Answer, Solution},
+};
 
-pub fn parse(input: &str) -> Data {
-    input
-        .split("\n\n")
-        .map(|l| l.lines().map(parse_packet_string).collect::<Vec<_>>())
-        .map(|pair| (pair[0].clone(), pair[1].clone()))
-        .collect()
-}
+// The packets are kept as text, `compare` doesn't need to build them
+pub type Data = Vec<(String, String)>;
 
+/// Packets are compared with the puzzle rules, an int is equal to a list that only contains it
 #[derive(Debug, Clone)]
 pub enum Packet {
     List(Vec<Packet>),
-    Int(i32),
-}
-
-#[allow(unused)]
-fn packet_string(packet: &Packet) -> String {
-    let mut s = String::from("");
-    match packet {
-        Packet::List(l) => write!(
-            s,
-            "[{}]",
-            l.iter().map(packet_string).collect::<Vec<_>>().join(",")
-        )
-        .unwrap(),
-        Packet::Int(i) => write!(s, "{i}").unwrap(),
-    };
-    s
-}
-
-fn parse_packet_string(s: &str) -> Packet {
-    let mut c = s.chars().collect::<VecDeque<_>>();
-    parse_packet(&mut c)[0].clone()
-}
-
-fn parse_packet(raw_packet: &mut VecDeque<char>) -> Vec<Packet> {
-    let mut number = String::from("");
-    let mut list = vec![];
-    while let Some(c) = raw_packet.pop_front() {
-        match c {
-            '[' => {
-                list.push(Packet::List(parse_packet(raw_packet)));
-            }
-            ']' => {
-                if !number.is_empty() {
-                    list.push(Packet::Int(number.parse().unwrap()));
-                }
-                break;
+    Int(u32),
+}
+
+impl Packet {
+    /// `text` must be a slice of `input`
+    pub fn parse(input: &str, text: &str) -> Result<Self, ParseError> {
+        let mut parser = PacketParser { input, rest: text };
+        let packet = parser.list()?;
+        if !parser.rest.is_empty() {
+            return Err(parse_error!(input, parser.rest, "the end of the packet"));
+        }
+        Ok(packet)
+    }
+}
+
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Packet::parse(s, s)
+    }
+}
+
+struct PacketParser<'a> {
+    input: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> PacketParser<'a> {
+    fn eat(&mut self, c: char) -> bool {
+        match self.rest.strip_prefix(c) {
+            Some(rest) => {
+                self.rest = rest;
+                true
             }
-            ',' => {
-                if !number.is_empty() {
-                    list.push(Packet::Int(number.parse().unwrap()));
-                    number = String::from("");
-                }
+            None => false,
+        }
+    }
+
+    fn list(&mut self) -> Result<Packet, ParseError> {
+        if !self.eat('[') {
+            return Err(parse_error!(self.input, self.rest, "`[`"));
+        }
+        let mut list = vec![];
+        if self.eat(']') {
+            return Ok(Packet::List(list));
+        }
+        loop {
+            list.push(self.packet()?);
+            if self.eat(']') {
+                return Ok(Packet::List(list));
             }
-            c if c.is_ascii_digit() => {
-                number = format!("{number}{c}");
+            if !self.eat(',') {
+                return Err(parse_error!(self.input, self.rest, "`,` or `]`"));
             }
-            _ => unreachable!(),
-        };
+        }
+    }
+
+    fn packet(&mut self) -> Result<Packet, ParseError> {
+        if self.rest.starts_with('[') {
+            return self.list();
+        }
+        let len = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let (int, rest) = self.rest.split_at(len);
+        let int = int
+            .parse()
+            .map_err(|_| parse_error!(self.input, self.rest, "a number or `[`"))?;
+        self.rest = rest;
+        Ok(Packet::Int(int))
     }
-    list
 }
 
-fn compare(left: &Packet, right: &Packet) -> std::cmp::Ordering {
-    use std::cmp::Ordering::*;
-    use Packet::*;
-    match (left, right) {
-        (Int(l), Int(r)) => l.cmp(r),
-        (List(l), List(r)) => {
-            let mut i = 0;
-            while i < l.len() && i < r.len() {
-                match compare(&l[i], &r[i]) {
-                    cmp @ (Less | Greater) => return cmp,
-                    Equal => i += 1,
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Int(int) => write!(f, "{int}"),
+            Packet::List(list) => {
+                write!(f, "[")?;
+                for (i, packet) in list.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{packet}")?;
                 }
+                write!(f, "]")
             }
-            l.len().cmp(&r.len())
         }
-        (list, Int(int)) => compare(list, &List(vec![Int(*int)])),
-        (Int(int), list) => compare(&List(vec![Int(*int)]), list),
     }
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let mut sum = 0;
-    for (i, (left, right)) in input.iter().enumerate() {
-        if let std::cmp::Ordering::Less = compare(left, right) {
-            sum += i + 1;
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use Packet::*;
+        match (self, other) {
+            (Int(l), Int(r)) => l.cmp(r),
+            (List(l), List(r)) => l.iter().cmp(r.iter()),
+            (List(l), Int(_)) => l.iter().cmp(std::slice::from_ref(other)),
+            (Int(_), List(r)) => std::slice::from_ref(self).iter().cmp(r.iter()),
         }
     }
-    sum
 }
 
-pub fn part_2(input: &Data) -> usize {
-    let mut packets = vec![];
-    let divider_2 = parse_packet_string("[[2]]");
-    let divider_6 = parse_packet_string("[[6]]");
-    packets.push(divider_2.clone());
-    packets.push(divider_6.clone());
-    for (left, right) in input.clone() {
-        packets.push(left);
-        packets.push(right);
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Packet {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Packet {}
+
+/// Checks that `line` is a packet without building it, the errors are the same as `Packet::parse`
+fn validate(input: &str, line: &str) -> Result<(), ParseError> {
+    let bytes = line.as_bytes();
+    let error = |i: usize, expected: &str| Err(parse_error!(input, &line[i..], expected));
+    if bytes.first() != Some(&b'[') {
+        return error(0, "`[`");
     }
-    packets.sort_by(compare);
-    let mut product = 0;
-    for (i, p) in packets.iter().enumerate() {
-        if compare(p, &divider_2).is_eq() {
-            product = i + 1;
-        } else if compare(p, &divider_6).is_eq() {
-            product *= i + 1;
+    let (mut i, mut depth) = (0, 0);
+    loop {
+        match bytes.get(i) {
+            Some(b'[') => {
+                depth += 1;
+                i += 1;
+                // an empty list is closed right away
+                if bytes.get(i) != Some(&b']') {
+                    continue;
+                }
+            }
+            Some(b'0'..=b'9') => {
+                let len = bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+                if line[i..i + len].parse::<u32>().is_err() {
+                    return error(i, "a number or `[`");
+                }
+                i += len;
+            }
+            _ => return error(i, "a number or `[`"),
         }
+        // after a packet, the lists it ends and the `,` before the next one
+        loop {
+            match bytes.get(i) {
+                Some(b']') => {
+                    depth -= 1;
+                    i += 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                Some(b',') => {
+                    i += 1;
+                    break;
+                }
+                _ => return error(i, "`,` or `]`"),
+            }
+        }
+        if depth == 0 {
+            break;
+        }
+    }
+    if i < bytes.len() {
+        return error(i, "the end of the packet");
     }
-    product
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Open,
+    Close,
+    Int(u32),
+}
+
+// One side of `compare`
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    token: Option<Token>,
+    /// `]` owed to an int that was promoted to a list
+    closes: usize,
+}
 
-    const INPUTS: &str = indoc! {"
-        [1,1,3,1,1]
-        [1,1,5,1,1]
+impl<'a> Cursor<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        let mut cursor = Self {
+            bytes,
+            token: None,
+            closes: 0,
+        };
+        cursor.advance();
+        cursor
+    }
+
+    fn advance(&mut self) {
+        if self.closes > 0 {
+            self.closes -= 1;
+            self.token = Some(Token::Close);
+            return;
+        }
+        while let [b',', rest @ ..] = self.bytes {
+            self.bytes = rest;
+        }
+        self.token = match self.bytes {
+            [] => None,
+            [b'[', rest @ ..] => {
+                self.bytes = rest;
+                Some(Token::Open)
+            }
+            [b']', rest @ ..] => {
+                self.bytes = rest;
+                Some(Token::Close)
+            }
+            _ => {
+                let mut int = 0;
+                while let [digit @ b'0'..=b'9', rest @ ..] = self.bytes {
+                    int = int * 10 + (digit - b'0') as u32;
+                    self.bytes = rest;
+                }
+                Some(Token::Int(int))
+            }
+        };
+    }
+}
 
-        [[1],[2,3,4]]
-        [[1],4]
+/// Same order as `Packet` but reads the packets as text, without allocating.
+/// The packets must be well formed.
+pub fn compare(left: &[u8], right: &[u8]) -> Ordering {
+    use Token::*;
 
-        [9]
-        [[8,7,6]]
+    let mut left = Cursor::new(left);
+    let mut right = Cursor::new(right);
+    loop {
+        match (left.token, right.token) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) | (Some(Close), Some(Open | Int(_))) => return Ordering::Less,
+            (Some(_), None) | (Some(Open | Int(_)), Some(Close)) => return Ordering::Greater,
+            (Some(Int(l)), Some(Int(r))) if l != r => return l.cmp(&r),
+            (Some(Int(_)), Some(Open)) => {
+                // the int becomes a list, it stays the current token until compared
+                right.advance();
+                left.closes += 1;
+            }
+            (Some(Open), Some(Int(_))) => {
+                left.advance();
+                right.closes += 1;
+            }
+            _ => {
+                left.advance();
+                right.advance();
+            }
+        }
+    }
+}
 
-        [[4,4],4,4]
-        [[4,4],4,4,4]
+pub struct Day13;
 
-        [7,7,7,7]
-        [7,7,7]
+impl Solution for Day13 {
+    type Input = Data;
 
-        []
-        [3]
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        input
+            .split("\n\n")
+            .map(|block| {
+                let mut lines = block.lines();
+                let mut next_packet = || {
+                    let line = lines.next().unwrap_or(&block[block.len()..]);
+                    validate(input, line)?;
+                    Ok(line.to_string())
+                };
+                let pair = (next_packet()?, next_packet()?);
+                if let Some(line) = lines.next() {
+                    return Err(parse_error!(input, line, "a blank line between pairs"));
+                }
+                Ok(pair)
+            })
+            .collect()
+    }
 
-        [[[]]]
-        [[]]
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(input
+            .iter()
+            .enumerate()
+            .filter(|(_, (left, right))| compare(left.as_bytes(), right.as_bytes()).is_lt())
+            .map(|(i, _)| i + 1)
+            .sum::<usize>()
+            .into())
+    }
 
-        [1,[2,[3,[4,[5,6,7]]]],8,9]
-        [1,[2,[3,[4,[5,6,0]]]],8,9]
-    "};
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        // no need to sort, the position of a divider is the number of packets before it
+        let before = |divider: &[u8]| {
+            input
+                .iter()
+                .flat_map(|(left, right)| [left, right])
+                .filter(|packet| compare(packet.as_bytes(), divider).is_lt())
+                .count()
+        };
+        // [[2]] is also before [[6]]
+        Ok(((before(b"[[2]]") + 1) * (before(b"[[6]]") + 2)).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day13;
+    use crate::{example, solution::Solution};
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 13);
+    pub fn packet() {
+        use super::Packet;
+
+        let packet: Packet = "[1,[2,[]],10]".parse().unwrap();
+        assert_eq!(packet.to_string(), "[1,[2,[]],10]");
+        assert_eq!(Packet::Int(3), "[[3]]".parse().unwrap());
+
+        let errors = [
+            ("1", 1, "`[`"),
+            ("[1,]", 4, "a number or `[`"),
+            ("[1[2]]", 3, "`,` or `]`"),
+            ("[[1]", 5, "`,` or `]`"),
+            ("[1]]", 4, "the end of the packet"),
+        ];
+        for (text, column, expected) in errors {
+            let err = text.parse::<Packet>().unwrap_err();
+            assert_eq!(
+                (err.column, err.expected.as_str()),
+                (column, expected),
+                "{text}"
+            );
+            assert_eq!(super::validate(text, text), Err(err), "{text}");
+        }
     }
 
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 140);
+    pub fn compare() {
+        use super::Packet;
+
+        let input = Day13::parse(&example::input("day13")).unwrap();
+        for (left, right) in &input {
+            let packets = (
+                left.parse::<Packet>().unwrap(),
+                right.parse::<Packet>().unwrap(),
+            );
+            let expected = packets.0.cmp(&packets.1);
+            assert_eq!(super::compare(left.as_bytes(), right.as_bytes()), expected);
+        }
+        assert!(super::compare(b"3", b"[[3]]").is_eq());
+        assert!(super::compare(b"[[3],4]", b"[3,[3]]").is_gt());
+
+        let err = Day13::parse("[1]\n[2]\n[garbage\n\n[3]\n[4]\n").unwrap_err();
+        assert_eq!(
+            (err.line, err.expected.as_str()),
+            (3, "a blank line between pairs")
+        );
+    }
+
+    mod properties {
+        use proptest::prelude::*;
+
+        use super::super::Packet;
+
+        fn packet() -> impl Strategy<Value = Packet> {
+            let int = (0..100u32).prop_map(Packet::Int);
+            int.prop_recursive(4, 32, 5, |inner| {
+                prop::collection::vec(inner, 0..5).prop_map(Packet::List)
+            })
+        }
+
+        fn list() -> impl Strategy<Value = Packet> {
+            prop::collection::vec(packet(), 0..5).prop_map(Packet::List)
+        }
+
+        proptest! {
+            #[test]
+            fn round_trip(packet in list()) {
+                let text = packet.to_string();
+                prop_assert!(super::super::validate(&text, &text).is_ok());
+                let parsed: Packet = text.parse().unwrap();
+                // `==` follows the puzzle order where 3 == [[3]], Debug shows the structure
+                prop_assert_eq!(format!("{parsed:?}"), format!("{packet:?}"));
+                prop_assert_eq!(parsed.to_string(), text);
+            }
+
+            #[test]
+            fn total_order(a in list(), b in list()) {
+                prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+                prop_assert_eq!(a == b, a.cmp(&b).is_eq());
+            }
+
+            #[test]
+            fn raw_compare(a in list(), b in list()) {
+                let (left, right) = (a.to_string(), b.to_string());
+                prop_assert_eq!(super::super::compare(left.as_bytes(), right.as_bytes()), a.cmp(&b));
+            }
+        }
     }
 }
@@ -1,120 +1,236 @@
-use hashbrown::HashSet;
-
-type Data = (HashSet<(usize, usize)>, usize);
-
-pub fn parse(input: &str) -> Data {
-    let input: Vec<Vec<(usize, usize)>> = input
-        .lines()
-        .map(|l| {
-            l.split(" -> ")
-                .flat_map(|x| x.split_once(','))
-                .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
-                .collect::<Vec<_>>()
-        })
-        .collect();
-
-    let mut map = HashSet::new();
-    let mut max_y = usize::MIN;
-    for line in input {
-        for window in line.windows(2) {
-            let [a, b] = window else { unreachable!(); };
-            for y in a.1.min(b.1)..=a.1.max(b.1) {
-                for x in a.0.min(b.0)..=a.0.max(b.0) {
-                    map.insert((x, y));
-                    max_y = max_y.max(y);
+use std::fmt;
+
+use colored::Color;
+
+use crate::{
+    export::Export,
+    grid::{Grid, Point},
+    parse::ParseError,
+    parse_error,
+    runner::Options,
+    solution::{Answer, Solution},
+    try_scan,
+    visualize::{Pixel, Player, ToPixel},
+};
+
+// The rock paths
+pub type Data = Vec<Vec<Point>>;
+
+pub const SOURCE: Point = (500, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+        };
+        write!(f, "{c}")
+    }
+}
+
+impl ToPixel for Tile {
+    fn to_pixel(&self) -> Option<Pixel> {
+        match self {
+            Tile::Air => None,
+            Tile::Rock => Some(Pixel::new('#', Color::White)),
+            Tile::Sand => Some(Pixel::new('o', Color::Yellow)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Floor {
+    /// The sand falls forever once it's below the lowest rock
+    Abyss,
+    /// An infinite floor 2 rows below the lowest rock
+    Solid,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cave {
+    /// Only covers the part of the cave the sand can reach
+    map: Grid<Tile>,
+    /// Cave x of the first column of the map
+    min_x: i32,
+    max_y: i32,
+    source: Point,
+    floor: Floor,
+}
+
+impl Cave {
+    /// `source` is where the sand comes from, it must be above the floor
+    pub fn new(paths: &[Vec<Point>], source: Point, floor: Floor) -> Self {
+        let points = || paths.iter().flatten();
+        let max_y = points()
+            .map(|(_, y)| *y)
+            .max()
+            .unwrap_or(source.1)
+            .max(source.1);
+        // The sand can't spread further than a triangle going down from the source to the floor,
+        // or further than one column beside the rocks before falling in the abyss
+        let spread = max_y + 2 - source.1;
+        let min_x = points()
+            .map(|(x, _)| *x)
+            .chain([source.0 - spread])
+            .min()
+            .unwrap()
+            - 1;
+        let max_x = points()
+            .map(|(x, _)| *x)
+            .chain([source.0 + spread])
+            .max()
+            .unwrap()
+            + 1;
+
+        // The floor itself isn't stored
+        let width = (max_x - min_x + 1) as usize;
+        let mut map = Grid::new(width, (max_y + 2) as usize, Tile::Air);
+        for path in paths {
+            for window in path.windows(2) {
+                let [a, b] = window else {
+                    unreachable!();
+                };
+                for y in a.1.min(b.1)..=a.1.max(b.1) {
+                    for x in a.0.min(b.0)..=a.0.max(b.0) {
+                        map[(x - min_x, y)] = Tile::Rock;
+                    }
                 }
             }
+            // a path with a single point is still a rock
+            if let [point] = path[..] {
+                map[(point.0 - min_x, point.1)] = Tile::Rock;
+            }
+        }
+        Self {
+            map,
+            min_x,
+            max_y,
+            source,
+            floor,
         }
     }
-    (map, max_y)
-}
 
-fn simulate_sand(map: &HashSet<(usize, usize)>, max_y: usize) -> (usize, usize) {
-    let sand_start = (500, 0);
-    let mut sand = sand_start;
-    while sand.1 <= max_y {
-        if !map.contains(&(sand.0, sand.1 + 1)) {
-            sand.1 += 1;
-        } else if !map.contains(&(sand.0 - 1, sand.1 + 1)) {
-            sand.0 -= 1;
-            sand.1 += 1;
-        } else if !map.contains(&(sand.0 + 1, sand.1 + 1)) {
-            sand.0 += 1;
-            sand.1 += 1;
-        } else {
-            break;
+    /// Map coordinates, the source is at `(source.x - min_x, source.y)`
+    pub fn map(&self) -> &Grid<Tile> {
+        &self.map
+    }
+
+    /// Pours sand until it falls in the abyss or blocks the source and returns how many grains
+    /// settled. `on_grain` sees the map every time a grain settles.
+    ///
+    /// A grain falls along the same path as the previous one until the cell where the previous
+    /// one settled, so each one starts from there instead of from the source.
+    pub fn fill(&mut self, mut on_grain: impl FnMut(&Grid<Tile>)) -> usize {
+        let source = (self.source.0 - self.min_x, self.source.1);
+        if self.map.get(source) != Some(&Tile::Air) {
+            return 0;
+        }
+        let mut path = vec![source];
+        let mut count = 0;
+        while let Some(&sand) = path.last() {
+            let next = [(0, 1), (-1, 1), (1, 1)]
+                .iter()
+                .map(|(dx, dy)| (sand.0 + dx, sand.1 + dy))
+                .find(|next| self.is_free(*next));
+            match next {
+                Some(next) if self.floor == Floor::Abyss && next.1 > self.max_y => break,
+                Some(next) => path.push(next),
+                None => {
+                    self.map[sand] = Tile::Sand;
+                    count += 1;
+                    on_grain(&self.map);
+                    path.pop();
+                }
+            }
         }
+        count
     }
-    sand
-}
 
-pub fn part_1((map, max_y): &Data) -> usize {
-    let mut map = map.clone();
-    let mut count = 0;
-    loop {
-        let sand = simulate_sand(&map, *max_y);
-        if sand.1 > *max_y {
-            break;
+    fn is_free(&self, point: Point) -> bool {
+        match self.map.get(point) {
+            Some(tile) => *tile == Tile::Air,
+            // below the map is the floor or the abyss
+            None => self.floor == Floor::Abyss,
         }
-        map.insert(sand);
-        count += 1;
     }
-    count
 }
 
-pub fn part_2((map, max_y): &Data) -> usize {
-    let mut map = map.clone();
-    let mut count = 0;
-    loop {
-        let sand = simulate_sand(&map, *max_y);
-        map.insert(sand);
-        count += 1;
-        if sand == (500, 0) {
-            break;
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        let paths: Vec<Vec<Point>> = input
+            .lines()
+            .map(|l| {
+                l.split(" -> ")
+                    .map(|point| {
+                        let (x, y): (usize, usize) = try_scan!(input, "{},{}" <- point)?;
+                        Ok((x as i32, y as i32))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+        if paths.is_empty() {
+            return Err(parse_error!(input, input, "at least one rock path"));
         }
+        Ok(paths)
     }
-    count
-}
 
-// std::thread::sleep(std::time::Duration::from_millis(5));
-// print_map(&map, *max_y);
-#[allow(unused)]
-fn print_map(map: &HashSet<(usize, usize)>, max_y: usize) {
-    // clear screen and reset cursor at top left
-    print!("\x1B[2J\x1B[1;1H");
-    println!();
-    for y in 0..=max_y + 2 {
-        for x in 450..=550 {
-            if !map.contains(&(x, y)) {
-                print!("#");
-            } else {
-                print!(" ");
-            }
+    fn extras(input: &Data, options: &Options) {
+        if let Some(mut player) = Player::from_options(options, module_path!()) {
+            Cave::new(input, SOURCE, Floor::Solid).fill(|map| player.show(map));
+        }
+        if let Some(export) = Export::from_options(options, module_path!()) {
+            let mut recorder = export.recorder(100);
+            let mut cave = Cave::new(input, SOURCE, Floor::Solid);
+            cave.fill(|map| recorder.record(map));
+            export.png(cave.map());
+            export.gif(&recorder);
         }
-        println!();
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(Cave::new(input, SOURCE, Floor::Abyss).fill(|_| {}).into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Ok(Cave::new(input, SOURCE, Floor::Solid).fill(|_| {}).into())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        498,4 -> 498,6 -> 496,6
-        503,4 -> 502,4 -> 502,9 -> 494,9
-    "};
+    use super::Day14;
+    use crate::{example, solution::Solution};
 
     #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 24);
-    }
+    pub fn cave() {
+        use super::{Cave, Floor};
 
-    #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 93);
+        // the triangle under the source has 16 cells, the rock is only in it for the 2nd source
+        let rock = [vec![(497, 2)]];
+        assert_eq!(Cave::new(&rock, (500, 0), Floor::Solid).fill(|_| {}), 16);
+        assert_eq!(Cave::new(&rock, (498, 0), Floor::Solid).fill(|_| {}), 15);
+        assert_eq!(Cave::new(&rock, (498, 0), Floor::Abyss).fill(|_| {}), 0);
+
+        let input = Day14::parse(&example::input("day14")).unwrap();
+        let mut cave = Cave::new(&input, (500, 0), Floor::Abyss);
+        let mut grains = 0;
+        cave.fill(|_| grains += 1);
+        let sand = cave
+            .map()
+            .iter()
+            .filter(|(_, tile)| **tile == super::Tile::Sand);
+        assert_eq!(sand.count(), grains);
     }
 }
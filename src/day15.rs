@@ -1,108 +1,283 @@
-use rayon::prelude::{IntoParallelIterator, ParallelIterator};
-use serde_scan::scan;
+use std::ops::RangeInclusive;
 
-type Data = Vec<((i32, i32), (i32, i32))>;
+use anyhow::bail;
+use hashbrown::HashSet;
 
-pub fn parse(input: &str) -> Data {
-    input
-        .lines()
-        .map(|line| scan!("Sensor at x={}, y={}: closest beacon is at x={}, y={}" <- line).unwrap())
-        .collect()
+use crate::{
+    grid::Point,
+    parse::ParseError,
+    solution::{Answer, Params, Solution},
+    try_scan,
+};
+
+pub type Data = Vec<Sensor>;
+
+// Can be changed with the `row`, `max` and `multiplier` parameters
+const TARGET_ROW: i32 = 2_000_000;
+const SEARCH_MAX: i32 = 4_000_000;
+const TUNING_MULTIPLIER: i64 = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sensor {
+    pub position: Point,
+    pub beacon: Point,
+    /// Distance to the beacon, the sensor covers a diamond of that radius
+    pub radius: i32,
+}
+
+impl Sensor {
+    fn covers(&self, point: Point) -> bool {
+        dist(self.position, point) <= self.radius
+    }
 }
 
-fn dist(a: (i32, i32), b: (i32, i32)) -> i32 {
+fn dist(a: Point, b: Point) -> i32 {
     (a.0 - b.0).abs() + (a.1 - b.1).abs()
 }
 
-fn intervals_in_row(input: &Data, row_target: i32) -> Vec<(i32, i32)> {
+pub fn intervals_in_row(sensors: &[Sensor], row: i32) -> Vec<(i32, i32)> {
     let mut intervals = vec![];
-    for (sensor, beacon) in input {
-        let d = dist(*sensor, *beacon);
-        let dx = d - (sensor.1 - row_target).abs();
-        if dx <= 0 {
-            continue;
+    for sensor in sensors {
+        let dx = sensor.radius - (sensor.position.1 - row).abs();
+        if dx >= 0 {
+            intervals.push((sensor.position.0 - dx, sensor.position.0 + dx));
         }
-        intervals.push((sensor.0 - dx, sensor.0 + dx));
     }
     merge_intervals(intervals)
 }
 
-fn merge_intervals(intervals: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
-    let mut intervals = intervals;
+pub fn merge_intervals(mut intervals: Vec<(i32, i32)>) -> Vec<(i32, i32)> {
     intervals.sort_unstable_by_key(|x| x.0);
-
-    let mut result = vec![];
-    let mut current = intervals[0];
-    for (start, end) in intervals.iter().skip(1) {
-        if *start <= current.1 {
-            current.1 = (*end).max(current.1);
-            continue;
-        } else {
-            result.push(current);
-            current = (*start, *end);
+    let mut result: Vec<(i32, i32)> = vec![];
+    for (start, end) in intervals {
+        match result.last_mut() {
+            Some(current) if start <= current.1 + 1 => current.1 = current.1.max(end),
+            _ => result.push((start, end)),
         }
     }
-    result.push(current);
     result
 }
 
-fn sum_intervals(intervals: Vec<(i32, i32)>) -> i32 {
-    intervals.iter().map(|(start, end)| end - start).sum()
+/// Number of positions in the row where the beacon can't be
+pub fn covered_in_row(sensors: &[Sensor], row: i32) -> usize {
+    let intervals = intervals_in_row(sensors, row);
+    let covered: i32 = intervals.iter().map(|(start, end)| end - start + 1).sum();
+    let beacons: HashSet<Point> = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.1 == row)
+        .collect();
+    covered as usize - beacons.len()
 }
 
-fn check_area(input: &Data, max: i32) -> usize {
-    let (intervals, y) = (0..=max)
-        .into_par_iter()
-        .map(|y| (intervals_in_row(input, y), y))
-        // if len is > 1 it means there's a gap
-        .find_any(|(interval, _)| interval.len() > 1)
-        .unwrap();
-    let x = intervals[0].1 + 1;
-    x as usize * 4000000 + y as usize
+/// The isolated points of the square search area that no sensor covers.
+///
+/// With u = x + y and v = x - y the sides of a diamond become straight lines. In most inputs the
+/// point is where the lines running along two one cell gaps between sensors cross, so those
+/// crossings are tried first. A gap can also end deep inside other diamonds with no line crossing
+/// it on the point, then the gap lines are walked. The last resort is the crossings of every side
+/// and of the corners of the area.
+pub fn uncovered(sensors: &[Sensor], area: RangeInclusive<i32>) -> Vec<Point> {
+    // (u lines, v lines) just outside every diamond, for each side: (line, towards +u or +v)
+    let outside = |sensor: &Sensor| {
+        let (x, y) = sensor.position;
+        let r = sensor.radius + 1;
+        let u = [(x + y - r, false), (x + y + r, true)];
+        let v = [(x - y - r, false), (x - y + r, true)];
+        (u, v)
+    };
+    let mut u_lines = HashSet::new();
+    let mut v_lines = HashSet::new();
+    for a in sensors {
+        for b in sensors {
+            let ((a_u, a_v), (b_u, b_v)) = (outside(a), outside(b));
+            for ((a_line, a_up), (b_line, b_up)) in a_u.into_iter().zip(b_u.into_iter().rev()) {
+                if a_line == b_line && a_up && !b_up {
+                    u_lines.insert(a_line);
+                }
+            }
+            for ((a_line, a_up), (b_line, b_up)) in a_v.into_iter().zip(b_v.into_iter().rev()) {
+                if a_line == b_line && a_up && !b_up {
+                    v_lines.insert(a_line);
+                }
+            }
+        }
+    }
+
+    let points = candidates(sensors, &u_lines, &v_lines, &area);
+    if !points.is_empty() {
+        return points;
+    }
+    let gaps = u_lines.iter().map(|u| Diagonal::U(*u));
+    let gaps = gaps.chain(v_lines.iter().map(|v| Diagonal::V(*v)));
+    let mut points: Vec<Point> = gaps
+        .flat_map(|line| uncovered_on_line(sensors, line, &area))
+        .collect();
+    if !points.is_empty() {
+        points.sort_unstable();
+        points.dedup();
+        return points;
+    }
+    // no gap between two sensors, the point is bounded by something else
+    for sensor in sensors {
+        let (u, v) = outside(sensor);
+        u_lines.extend(u.map(|(line, _)| line));
+        v_lines.extend(v.map(|(line, _)| line));
+    }
+    for corner in [(*area.start(), *area.start()), (*area.end(), *area.end())] {
+        u_lines.insert(corner.0 + corner.1);
+        v_lines.insert(corner.0 - corner.1);
+    }
+    for corner in [(*area.start(), *area.end()), (*area.end(), *area.start())] {
+        u_lines.insert(corner.0 + corner.1);
+        v_lines.insert(corner.0 - corner.1);
+    }
+    candidates(sensors, &u_lines, &v_lines, &area)
 }
 
-pub fn part_1(input: &Data) -> usize {
-    let intervals = intervals_in_row(input, 2000000);
-    sum_intervals(intervals) as usize
+#[derive(Debug, Clone, Copy)]
+enum Diagonal {
+    /// x + y = u
+    U(i32),
+    /// x - y = v
+    V(i32),
 }
 
-pub fn part_2(input: &Data) -> usize {
-    check_area(input, 4000000)
+/// The cells of the line inside the area that no sensor covers, the line is followed along y
+fn uncovered_on_line(sensors: &[Sensor], line: Diagonal, area: &RangeInclusive<i32>) -> Vec<Point> {
+    let (start, end) = (*area.start(), *area.end());
+    // x = offset + slope * y
+    let (offset, slope, ys) = match line {
+        Diagonal::U(u) => (u, -1, (u - end)..=(u - start)),
+        Diagonal::V(v) => (v, 1, (start - v)..=(end - v)),
+    };
+    let ys = (*ys.start()).max(start)..=(*ys.end()).min(end);
+    let mut covered = vec![];
+    for sensor in sensors {
+        let (x, y) = sensor.position;
+        // |offset + slope * y - x| + |y - sy| <= radius, both terms change by 1 along the line
+        let a = slope * (x - offset);
+        let (low, high) = (a.min(y), a.max(y));
+        if high - low <= sensor.radius {
+            let spare = sensor.radius - (high - low);
+            covered.push((low - spare / 2, high + spare / 2));
+        }
+    }
+    let mut points = vec![];
+    let mut next = *ys.start();
+    for (from, to) in merge_intervals(covered) {
+        points.extend((next..from.min(ys.end() + 1)).map(|y| (offset + slope * y, y)));
+        next = next.max(to + 1);
+    }
+    points.extend((next..=*ys.end()).map(|y| (offset + slope * y, y)));
+    points
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-
-    const INPUTS: &str = indoc! {"
-        Sensor at x=2, y=18: closest beacon is at x=-2, y=15
-        Sensor at x=9, y=16: closest beacon is at x=10, y=16
-        Sensor at x=13, y=2: closest beacon is at x=15, y=3
-        Sensor at x=12, y=14: closest beacon is at x=10, y=16
-        Sensor at x=10, y=20: closest beacon is at x=10, y=16
-        Sensor at x=14, y=17: closest beacon is at x=10, y=16
-        Sensor at x=8, y=7: closest beacon is at x=2, y=10
-        Sensor at x=2, y=0: closest beacon is at x=2, y=10
-        Sensor at x=0, y=11: closest beacon is at x=2, y=10
-        Sensor at x=20, y=14: closest beacon is at x=25, y=17
-        Sensor at x=17, y=20: closest beacon is at x=21, y=22
-        Sensor at x=16, y=7: closest beacon is at x=15, y=3
-        Sensor at x=14, y=3: closest beacon is at x=15, y=3
-        Sensor at x=20, y=1: closest beacon is at x=15, y=3
-    "};
+fn candidates(
+    sensors: &[Sensor],
+    u_lines: &HashSet<i32>,
+    v_lines: &HashSet<i32>,
+    area: &RangeInclusive<i32>,
+) -> Vec<Point> {
+    let mut points = vec![];
+    for u in u_lines {
+        for v in v_lines {
+            // the lines only cross on a cell when u and v have the same parity
+            if (u - v) % 2 != 0 {
+                continue;
+            }
+            let point = ((u + v) / 2, (u - v) / 2);
+            if area.contains(&point.0)
+                && area.contains(&point.1)
+                && !sensors.iter().any(|sensor| sensor.covers(point))
+            {
+                points.push(point);
+            }
+        }
+    }
+    points.sort_unstable();
+    points.dedup();
+    points
+}
 
-    #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let intervals = super::intervals_in_row(&input, 10);
-        let sum = super::sum_intervals(intervals);
-        assert_eq!(sum, 26);
+pub fn tuning_frequency(point: Point, multiplier: i64) -> i64 {
+    point.0 as i64 * multiplier + point.1 as i64
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let (sx, sy, bx, by) = try_scan!(
+                    input,
+                    "Sensor at x={}, y={}: closest beacon is at x={}, y={}" <- line
+                )?;
+                Ok(Sensor {
+                    position: (sx, sy),
+                    beacon: (bx, by),
+                    radius: dist((sx, sy), (bx, by)),
+                })
+            })
+            .collect()
+    }
+
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Self::part_1_with(input, &Params::default())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        Self::part_2_with(input, &Params::default())
     }
 
+    fn part_1_with(input: &Data, params: &Params) -> anyhow::Result<Answer> {
+        Ok(covered_in_row(input, params.get("row", TARGET_ROW)?).into())
+    }
+
+    fn part_2_with(input: &Data, params: &Params) -> anyhow::Result<Answer> {
+        let points = uncovered(input, 0..=params.get("max", SEARCH_MAX)?);
+        let point = match points[..] {
+            [point] => point,
+            [] => bail!("every point of the search area is covered"),
+            _ => bail!("{} points aren't covered, expected one", points.len()),
+        };
+        Ok(tuning_frequency(point, params.get("multiplier", TUNING_MULTIPLIER)?).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day15, Sensor};
+    use crate::{example, solution::Solution};
+
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::check_area(&input, 20);
+    pub fn uncovered() {
+        let input = Day15::parse(&example::input("day15")).unwrap();
+        let points = super::uncovered(&input, 0..=20);
+        assert_eq!(points, vec![(14, 11)]);
+        let result = super::tuning_frequency(points[0], 4_000_000);
         assert_eq!(result, 56000011);
+
+        // in a corner of the search area, there's no gap between two sensors
+        let input = Day15::parse("Sensor at x=5, y=5: closest beacon is at x=5, y=0\n").unwrap();
+        let points = super::uncovered(&input, 0..=10);
+        assert!(points.contains(&(0, 0)) && points.contains(&(10, 10)));
+        assert!(points.iter().all(|p| !input[0].covers(*p)));
+        assert!(super::uncovered(&input, 3..=7).is_empty());
+
+        // (2, 3) is on a one cell gap but no other gap crosses it
+        let sensors: Vec<_> = [((5, 0), 5), ((0, 7), 5), ((0, 0), 3), ((6, 5), 4)]
+            .into_iter()
+            .map(|(position, radius)| Sensor {
+                position,
+                beacon: (position.0 + radius, position.1),
+                radius,
+            })
+            .collect();
+        assert_eq!(super::uncovered(&sensors, 0..=7), vec![(2, 3)]);
     }
 }
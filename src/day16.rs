@@ -1,35 +1,200 @@
-type Data = Vec<usize>;
+use std::cmp::Reverse;
 
-pub fn parse(input: &str) -> Data {
-    input.lines().map(|l| l.parse().unwrap()).collect()
+use hashbrown::HashMap;
+
+use crate::{
+    parse::ParseError,
+    parse_error, search,
+    solution::{Answer, Solution},
+    try_scan,
+};
+
+pub type Data = Graph;
+
+#[derive(Debug)]
+struct Valve<'a> {
+    name: &'a str,
+    flow_rate: usize,
+    tunnels: Vec<&'a str>,
 }
 
-pub fn part_1(input: &Data) -> usize {
-    0
+// Only contains the start valve and the valves with a non-zero flow rate
+#[derive(Debug)]
+pub struct Graph {
+    flow_rates: Vec<usize>,
+    // shortest distance between each pair of valves
+    distances: Vec<Vec<usize>>,
+    start: usize,
 }
 
-pub fn part_2(input: &Data) -> usize {
-    0
+fn parse_valve<'a>(input: &str, line: &'a str) -> Result<Valve<'a>, ParseError> {
+    let expected = "`Valve {} has flow rate={}; tunnels lead to valves {}`";
+    let (valve, tunnels) = line
+        .split_once("; ")
+        .ok_or_else(|| parse_error!(input, line, expected))?;
+    let (name, flow_rate) = try_scan!(input, "Valve {} has flow rate={}" <- valve)?;
+    let (_, tunnels) = tunnels
+        .split_once("valves ")
+        .or_else(|| tunnels.split_once("valve "))
+        .ok_or_else(|| parse_error!(input, tunnels, expected))?;
+    Ok(Valve {
+        name,
+        flow_rate,
+        tunnels: tunnels.split(", ").collect(),
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
+fn compress(input: &str, valves: &[Valve]) -> Result<Graph, ParseError> {
+    let indices: HashMap<&str, usize> = valves
+        .iter()
+        .enumerate()
+        .map(|(i, valve)| (valve.name, i))
+        .collect();
+    let tunnels: Vec<Vec<usize>> = valves
+        .iter()
+        .map(|valve| {
+            valve
+                .tunnels
+                .iter()
+                .map(|t| {
+                    indices
+                        .get(t)
+                        .copied()
+                        .ok_or_else(|| parse_error!(input, t, "the name of a valve"))
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
 
-    const INPUTS: &str = indoc! {"
-    "};
+    let start = *indices
+        .get("AA")
+        .ok_or_else(|| parse_error!(input, &input[input.len()..], "a valve named AA"))?;
+    let relevant: Vec<usize> = (0..valves.len())
+        .filter(|i| *i == start || valves[*i].flow_rate > 0)
+        .collect();
+    // the opened valves are the bits of a u64
+    if let Some(valve) = relevant.get(u64::BITS as usize) {
+        let expected = "at most 64 valves with a flow rate, AA included";
+        return Err(parse_error!(input, valves[*valve].name, expected));
+    }
 
-    #[test]
-    pub fn part_1() {
-        let input = super::parse(INPUTS);
-        let result = super::part_1(&input);
-        assert_eq!(result, 0);
+    let distances = relevant
+        .iter()
+        .map(|from| {
+            let steps = search::reachable([*from], |valve| tunnels[*valve].iter().copied());
+            relevant
+                .iter()
+                .map(|to| steps.get(to).copied().unwrap_or(usize::MAX))
+                .collect()
+        })
+        .collect();
+
+    Ok(Graph {
+        flow_rates: relevant.iter().map(|i| valves[*i].flow_rate).collect(),
+        distances,
+        start: relevant.iter().position(|i| *i == start).unwrap(),
+    })
+}
+
+// Finds the best pressure that can be released for every set of opened valves
+fn max_pressure_per_set(graph: &Graph, time: usize) -> HashMap<u64, usize> {
+    let mut best = HashMap::new();
+    visit(graph, graph.start, time, 0, 0, &mut best);
+    best
+}
+
+fn visit(
+    graph: &Graph,
+    valve: usize,
+    time_left: usize,
+    opened: u64,
+    pressure: usize,
+    best: &mut HashMap<u64, usize>,
+) {
+    let entry = best.entry(opened).or_default();
+    *entry = pressure.max(*entry);
+    for (next, flow_rate) in graph.flow_rates.iter().enumerate() {
+        if *flow_rate == 0 || opened & (1 << next) != 0 {
+            continue;
+        }
+        // moving to the valve and opening it
+        let cost = graph.distances[valve][next].saturating_add(1);
+        if cost >= time_left {
+            continue;
+        }
+        let time_left = time_left - cost;
+        visit(
+            graph,
+            next,
+            time_left,
+            opened | (1 << next),
+            pressure + time_left * flow_rate,
+            best,
+        );
+    }
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Data;
+
+    fn parse(input: &str) -> Result<Data, ParseError> {
+        let valves = input
+            .lines()
+            .map(|line| parse_valve(input, line))
+            .collect::<Result<Vec<_>, _>>()?;
+        compress(input, &valves)
     }
 
+    fn part_1(input: &Data) -> anyhow::Result<Answer> {
+        Ok(max_pressure_per_set(input, 30)
+            .into_values()
+            .max()
+            .unwrap()
+            .into())
+    }
+
+    fn part_2(input: &Data) -> anyhow::Result<Answer> {
+        let mut best = max_pressure_per_set(input, 26)
+            .into_iter()
+            .collect::<Vec<_>>();
+        best.sort_unstable_by_key(|(_, pressure)| Reverse(*pressure));
+
+        // you and the elephant need to open a disjoint set of valves
+        let mut max = 0;
+        for (i, (you_opened, you)) in best.iter().enumerate() {
+            if you * 2 <= max {
+                break;
+            }
+            for (elephant_opened, elephant) in &best[i..] {
+                if you + elephant <= max {
+                    break;
+                }
+                if you_opened & elephant_opened == 0 {
+                    max = you + elephant;
+                }
+            }
+        }
+        Ok(max.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Day16;
+    use crate::solution::Solution;
+
     #[test]
-    pub fn part_2() {
-        let input = super::parse(INPUTS);
-        let result = super::part_2(&input);
-        assert_eq!(result, 0);
+    pub fn parse() {
+        let mut input = String::from("Valve AA has flow rate=0; tunnels lead to valves V0\n");
+        for i in 0..64 {
+            input += &format!("Valve V{i} has flow rate=1; tunnel leads to valve AA\n");
+        }
+        let err = Day16::parse(&input).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (65, 7, "V63"));
+        // AA also takes a bit, with one valve less it fits
+        let input = input.replacen("V0 has flow rate=1", "V0 has flow rate=0", 1);
+        assert!(Day16::parse(&input).is_ok());
     }
 }
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use serde_derive::Deserialize;
+
+use crate::{
+    input,
+    runner::YEAR,
+    solution::{Answer, Params},
+};
+
+/// An example given in the puzzle, read from `examples/2022/NN.txt`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub parts: [ExamplePart; 2],
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExamplePart {
+    /// None when the puzzle doesn't give the answer of this part for this example
+    pub answer: Option<Answer>,
+    pub params: Params,
+}
+
+// The files are TOML, a part is either its answer or a table with the answer and the parameters:
+//
+// [[example]]
+// input = '''
+// ...
+// '''
+// part_1 = { answer = 26, row = 10 }
+// part_2 = 56000011
+#[derive(Deserialize)]
+struct File {
+    example: Vec<RawExample>,
+}
+
+#[derive(Deserialize)]
+struct RawExample {
+    input: String,
+    part_1: Option<toml::Value>,
+    part_2: Option<toml::Value>,
+}
+
+pub fn path(day: &str) -> PathBuf {
+    PathBuf::from(format!("examples/{YEAR}/{}.txt", &day[3..]))
+}
+
+/// Every example of a day, `day` is its name like `day07`
+pub fn read(day: &str) -> anyhow::Result<Vec<Example>> {
+    let path = path(day);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&text).with_context(|| format!("Invalid example in {}", path.display()))
+}
+
+pub fn parse(text: &str) -> anyhow::Result<Vec<Example>> {
+    let file: File = toml::from_str(text)?;
+    file.example
+        .into_iter()
+        .map(|example| {
+            Ok(Example {
+                input: input::normalize(&example.input),
+                parts: [part(example.part_1)?, part(example.part_2)?],
+            })
+        })
+        .collect()
+}
+
+fn part(value: Option<toml::Value>) -> anyhow::Result<ExamplePart> {
+    let Some(value) = value else {
+        return Ok(ExamplePart::default());
+    };
+    let toml::Value::Table(mut table) = value else {
+        return Ok(ExamplePart {
+            answer: Some(answer(value)?),
+            params: Params::default(),
+        });
+    };
+    let answer = table.remove("answer").map(answer).transpose()?;
+    let params = table
+        .into_iter()
+        .map(|(name, value)| match value {
+            toml::Value::Integer(value) => Ok((name, value)),
+            _ => bail!("the parameter {name} must be an integer"),
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(ExamplePart { answer, params })
+}
+
+fn answer(value: toml::Value) -> anyhow::Result<Answer> {
+    Ok(match value {
+        toml::Value::Integer(value) => Answer::Int(value),
+        toml::Value::String(text) if text.trim_end().contains('\n') => {
+            Answer::Lines(text.trim_end().lines().map(String::from).collect())
+        }
+        toml::Value::String(text) => Answer::Text(text),
+        value => bail!("an answer must be a number or a string, found {value}"),
+    })
+}
+
+/// The input of the first example of a day
+#[cfg(test)]
+pub fn input(day: &str) -> String {
+    read(day).unwrap().swap_remove(0).input
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::{Answer, Params};
+
+    #[test]
+    pub fn parse() {
+        let text = r#"
+            [[example]]
+            input = '''
+              1 2
+            3
+            '''
+            part_1 = { answer = 26, row = 10 }
+            part_2 = "AB"
+
+            [[example]]
+            input = "4\r\n"
+            part_2 = '''
+            #.
+            .#
+            '''
+        "#;
+        let examples = super::parse(text).unwrap();
+        assert_eq!(examples.len(), 2);
+        // toml removes the first line break and the indentation is kept
+        assert_eq!(examples[0].input, "              1 2\n            3\n");
+        let part_1 = &examples[0].parts[0];
+        assert_eq!(part_1.answer, Some(Answer::Int(26)));
+        assert_eq!(part_1.params.get("row", 2_000_000).unwrap(), 10);
+        assert_eq!(part_1.params.get("max", 20).unwrap(), 20);
+        assert_eq!(examples[0].parts[1].answer, Some(Answer::from("AB")));
+
+        assert_eq!(examples[1].input, "4\n");
+        assert_eq!(examples[1].parts[0].answer, None);
+        assert_eq!(examples[1].parts[0].params, Params::default());
+        let lines = Answer::Lines(vec!["            #.".into(), "            .#".into()]);
+        assert_eq!(examples[1].parts[1].answer, Some(lines));
+
+        assert!(super::parse("[[example]]\ninput = 'x'\npart_1 = { row = 'a' }").is_err());
+    }
+}
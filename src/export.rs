@@ -0,0 +1,300 @@
+use std::{
+    borrow::Cow,
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use colored::Color;
+
+use crate::{
+    grid::{Grid, Point},
+    runner::Options,
+    visualize::{Frame, Pixel},
+};
+
+const BACKGROUND: [u8; 3] = [15, 15, 35];
+
+/// Same colors as most terminals
+pub fn rgb(color: Color) -> [u8; 3] {
+    match color {
+        Color::Black => [0, 0, 0],
+        Color::Red => [205, 49, 49],
+        Color::Green => [13, 188, 121],
+        Color::Yellow => [229, 229, 16],
+        Color::Blue => [36, 114, 200],
+        Color::Magenta => [188, 63, 188],
+        Color::Cyan => [17, 168, 205],
+        Color::White => [229, 229, 229],
+        Color::BrightBlack => [102, 102, 102],
+        Color::BrightRed => [241, 76, 76],
+        Color::BrightGreen => [35, 209, 139],
+        Color::BrightYellow => [245, 245, 67],
+        Color::BrightBlue => [59, 142, 234],
+        Color::BrightMagenta => [214, 112, 214],
+        Color::BrightCyan => [41, 184, 219],
+        Color::BrightWhite => [255, 255, 255],
+        Color::TrueColor { r, g, b } => [r, g, b],
+    }
+}
+
+/// A frame turned into colors, every cell is a square of `scale` pixels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Row by row
+    pub pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    pub fn new(frame: &impl Frame, scale: usize) -> Self {
+        let pixels = frame.pixels();
+        let viewport = bounds(&pixels).unwrap_or(((0, 0), (0, 0)));
+        Self::rasterize(&pixels, viewport, scale)
+    }
+
+    // (top left, bottom right) of the viewport, both included
+    fn rasterize(pixels: &[(Point, Pixel)], (min, max): (Point, Point), scale: usize) -> Self {
+        let cells_wide = (max.0 - min.0 + 1) as usize;
+        let cells_high = (max.1 - min.1 + 1) as usize;
+        let mut cells = Grid::new(cells_wide, cells_high, BACKGROUND);
+        for (point, pixel) in pixels {
+            if let Some(cell) = cells.get_mut((point.0 - min.0, point.1 - min.1)) {
+                *cell = rgb(pixel.color);
+            }
+        }
+        let (width, height) = (cells_wide * scale, cells_high * scale);
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| cells[((x / scale) as i32, (y / scale) as i32)])
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path).with_context(|| format!("Failed to create {path:?}"))?;
+        let mut encoder =
+            png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels.concat())?;
+        Ok(())
+    }
+}
+
+/// Collects frames and saves them as an animated GIF, the viewport fits every frame
+pub struct GifRecorder {
+    frames: Vec<Vec<(Point, Pixel)>>,
+    scale: usize,
+    /// In hundredths of a second
+    delay: u16,
+    keep_every: usize,
+    seen: usize,
+}
+
+impl GifRecorder {
+    pub fn new(fps: u16, scale: usize) -> Self {
+        Self {
+            frames: vec![],
+            scale,
+            delay: 100 / fps.clamp(1, 100),
+            keep_every: 1,
+            seen: 0,
+        }
+    }
+
+    /// Only keeps one frame out of `n`, long simulations would make huge files otherwise
+    pub fn keep_every(mut self, n: usize) -> Self {
+        self.keep_every = n.max(1);
+        self
+    }
+
+    pub fn record(&mut self, frame: &impl Frame) {
+        if self.seen.is_multiple_of(self.keep_every) {
+            self.frames.push(frame.pixels());
+        }
+        self.seen += 1;
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let all_pixels: Vec<_> = self.frames.iter().flatten().copied().collect();
+        let Some(viewport) = bounds(&all_pixels) else {
+            bail!("No frame was recorded");
+        };
+        let images: Vec<_> = self
+            .frames
+            .iter()
+            .map(|pixels| Image::rasterize(pixels, viewport, self.scale))
+            .collect();
+        let (width, height) = (images[0].width, images[0].height);
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            bail!("{width}x{height} is too big for a gif, use a smaller scale");
+        }
+
+        // gifs use indexed colors
+        let mut palette = vec![BACKGROUND];
+        for image in &images {
+            for color in &image.pixels {
+                if !palette.contains(color) {
+                    palette.push(*color);
+                }
+            }
+        }
+        if palette.len() > 256 {
+            bail!(
+                "A gif can't have more than 256 colors, found {}",
+                palette.len()
+            );
+        }
+
+        let file = File::create(path).with_context(|| format!("Failed to create {path:?}"))?;
+        let mut encoder = gif::Encoder::new(
+            BufWriter::new(file),
+            width as u16,
+            height as u16,
+            &palette.concat(),
+        )?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for image in images {
+            let indices: Vec<u8> = image
+                .pixels
+                .iter()
+                .map(|color| palette.iter().position(|c| c == color).unwrap() as u8)
+                .collect();
+            let frame = gif::Frame {
+                width: width as u16,
+                height: height as u16,
+                delay: self.delay,
+                buffer: Cow::Owned(indices),
+                ..Default::default()
+            };
+            encoder.write_frame(&frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// Used by `cargo run -- --export day12 [--out renders] [--scale 4] [--every n]`
+pub struct Export {
+    dir: PathBuf,
+    day: String,
+    scale: usize,
+    every: Option<usize>,
+}
+
+impl Export {
+    /// `day` is the `module_path!()` of the day
+    pub fn from_options(options: &Options, day: &str) -> Option<Self> {
+        if !options.export(day) {
+            return None;
+        }
+        Some(Self {
+            dir: options.out.clone(),
+            day: day.rsplit("::").next().unwrap_or(day).into(),
+            scale: options.scale,
+            every: options.every,
+        })
+    }
+
+    /// Keeps one frame out of `every` unless the command line says otherwise
+    pub fn recorder(&self, every: usize) -> GifRecorder {
+        GifRecorder::new(30, self.scale).keep_every(self.every.unwrap_or(every))
+    }
+
+    pub fn png(&self, frame: &impl Frame) {
+        let path = self.dir.join(format!("{}.png", self.day));
+        self.report(&path, || Image::new(frame, self.scale).save_png(&path));
+    }
+
+    pub fn gif(&self, recorder: &GifRecorder) {
+        let path = self.dir.join(format!("{}.gif", self.day));
+        self.report(&path, || recorder.save(&path));
+    }
+
+    fn report(&self, path: &Path, save: impl FnOnce() -> anyhow::Result<()>) {
+        let result = std::fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {:?}", self.dir))
+            .and_then(|_| save());
+        match result {
+            Ok(()) => eprintln!("{} saved to {path:?}", self.day),
+            Err(err) => eprintln!(
+                "{:?}",
+                err.context(format!("Failed to export {}", self.day))
+            ),
+        }
+    }
+}
+
+// (top left, bottom right) of the pixels, both included
+fn bounds(pixels: &[(Point, Pixel)]) -> Option<(Point, Point)> {
+    let min_x = pixels.iter().map(|(p, _)| p.0).min()?;
+    let min_y = pixels.iter().map(|(p, _)| p.1).min()?;
+    let max_x = pixels.iter().map(|(p, _)| p.0).max()?;
+    let max_y = pixels.iter().map(|(p, _)| p.1).max()?;
+    Some(((min_x, min_y), (max_x, max_y)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use colored::Color;
+
+    use super::{GifRecorder, Image, BACKGROUND};
+    use crate::visualize::Pixel;
+
+    #[test]
+    pub fn png() {
+        let frame = vec![
+            ((1, 1), Pixel::new('#', Color::Red)),
+            (
+                (2, 2),
+                Pixel::new('o', Color::TrueColor { r: 1, g: 2, b: 3 }),
+            ),
+        ];
+        let image = Image::new(&frame, 2);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[0], [205, 49, 49]);
+        assert_eq!(image.pixels[2], BACKGROUND);
+        assert_eq!(image.pixels[15], [1, 2, 3]);
+
+        let path = std::env::temp_dir().join("advent_of_code_2022_export.png");
+        image.save_png(&path).unwrap();
+        let decoder = png::Decoder::new(File::open(&path).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (4, 4));
+    }
+
+    #[test]
+    pub fn gif() {
+        use crate::{
+            day14::{Cave, Day14, Floor, SOURCE},
+            solution::Solution,
+        };
+
+        let input = Day14::parse("498,4 -> 498,6 -> 496,6\n").unwrap();
+        let mut recorder = GifRecorder::new(10, 1).keep_every(2);
+        let mut cave = Cave::new(&input, SOURCE, Floor::Solid);
+        let grains = cave.fill(|map| recorder.record(map));
+        let path = std::env::temp_dir().join("advent_of_code_2022_export.gif");
+        recorder.save(&path).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new()
+            .read_info(File::open(&path).unwrap())
+            .unwrap();
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, grains.div_ceil(2));
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::parse::ParseError;
+
+// (x, y), y grows downward
+pub type Point = (i32, i32);
+
+pub const NEIGHBOURS_4: [Point; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+pub const NEIGHBOURS_8: [Point; 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// Dense 2D grid stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Every line is a row and every char is a cell, `f` returns `None` for invalid chars.
+    /// All the lines must have the same length.
+    /// `module` is the `module_path!()` of the day, it's used for the error.
+    pub fn parse(
+        module: &'static str,
+        input: &str,
+        expected: &str,
+        mut f: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = vec![];
+        for line in input.lines() {
+            for (i, c) in line.char_indices() {
+                let cell =
+                    f(c).ok_or_else(|| ParseError::new(module, input, &line[i..], expected))?;
+                cells.push(cell);
+            }
+            if height == 0 {
+                width = cells.len();
+            }
+            height += 1;
+            if cells.len() != width * height {
+                let expected = format!("a row of {width} cells");
+                return Err(ParseError::new(module, input, line, expected));
+            }
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| point.1 as usize * self.width + point.0 as usize)
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|i| &mut self.cells[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.cells.iter().enumerate().map(|(i, cell)| {
+            let point = ((i % self.width) as i32, (i / self.width) as i32);
+            (point, cell)
+        })
+    }
+
+    pub fn position(&self, mut f: impl FnMut(&T) -> bool) -> Option<Point> {
+        self.iter()
+            .find(|(_, cell)| f(cell))
+            .map(|(point, _)| point)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Cells directly above, below, left and right of the point that are inside the grid
+    pub fn neighbours_4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.offsets(point, &NEIGHBOURS_4)
+    }
+
+    /// Same as `neighbours_4` but also includes the diagonals
+    pub fn neighbours_8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.offsets(point, &NEIGHBOURS_8)
+    }
+
+    fn offsets<'a>(
+        &'a self,
+        (x, y): Point,
+        offsets: &'a [Point],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        offsets.iter().flat_map(move |(dx, dy)| {
+            let point = (x + dx, y + dy);
+            self.get(point).map(|cell| (point, cell))
+        })
+    }
+
+    /// Walks from the point in the given direction until it leaves the grid.
+    /// The starting point is not included, a (0, 0) direction gives nothing.
+    pub fn ray(&self, (x, y): Point, (dx, dy): Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+        // it would never leave the grid
+        let len = if (dx, dy) == (0, 0) { 0 } else { i32::MAX };
+        (1..=len)
+            .map(move |i| (x + dx * i, y + dy * i))
+            .map_while(|point| self.get(point).map(|cell| (point, cell)))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{point:?} is outside of the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::Grid;
+    use crate::parse::ParseError;
+
+    const INPUTS: &str = indoc! {"
+        123
+        456
+    "};
+
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(module_path!(), input, "a digit", |c| {
+            c.is_ascii_digit().then_some(c)
+        })
+    }
+
+    #[test]
+    pub fn parse_digits() {
+        let grid = Grid::parse(module_path!(), INPUTS, "a digit", |c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get((2, 1)), Some(&6));
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.to_string(), INPUTS);
+
+        let err = parse("123\n4x6").unwrap_err();
+        assert_eq!(
+            (err.line, err.column, err.expected.as_str()),
+            (2, 2, "a digit")
+        );
+        let err = parse("123\n45").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    pub fn neighbours() {
+        let grid = parse(INPUTS).unwrap();
+        let n4 = grid
+            .neighbours_4((0, 0))
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        assert_eq!(n4, "24");
+        let n8 = grid
+            .neighbours_8((1, 0))
+            .map(|(_, c)| *c)
+            .collect::<String>();
+        assert_eq!(n8, "13456");
+    }
+
+    #[test]
+    pub fn ray() {
+        let grid = parse(INPUTS).unwrap();
+        let ray = grid.ray((0, 1), (1, 0)).collect::<Vec<_>>();
+        assert_eq!(ray, vec![((1, 1), &'5'), ((2, 1), &'6')]);
+        assert_eq!(grid.ray((0, 0), (0, -1)).count(), 0);
+        assert_eq!(grid.ray((1, 1), (0, 0)).count(), 0);
+    }
+}
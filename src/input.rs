@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+/// How the text of an input is cleaned up before it's parsed, so the days only have to deal with
+/// `\n` line endings and a single newline at the end
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalize {
+    /// Removes the byte order mark some editors write at the start of a file
+    pub strip_bom: bool,
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Self { strip_bom: true }
+    }
+}
+
+impl Normalize {
+    /// Line endings become `\n`, the whitespace at the end of each line and the blank lines at the
+    /// end of the input are removed
+    pub fn apply(&self, raw: &str) -> String {
+        let text = match raw.strip_prefix('\u{feff}') {
+            Some(text) if self.strip_bom => text,
+            _ => raw,
+        };
+        let mut normalized = String::with_capacity(text.len());
+        // `lines` already removes the `\r` of `\r\n`
+        for line in text.lines() {
+            normalized.push_str(line.trim_end());
+            normalized.push('\n');
+        }
+        normalized.truncate(normalized.trim_end_matches('\n').len());
+        if !normalized.is_empty() {
+            normalized.push('\n');
+        }
+        normalized
+    }
+}
+
+pub fn normalize(raw: &str) -> String {
+    Normalize::default().apply(raw)
+}
+
+/// Reads an input file and normalizes it
+pub fn read(path: impl AsRef<Path>) -> anyhow::Result<String> {
+    let path = path.as_ref();
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(normalize(&raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Normalize;
+
+    #[test]
+    pub fn normalize() {
+        let expected = "1 2\n\n3\n";
+        assert_eq!(super::normalize("1 2\r\n\r\n3\r\n"), expected);
+        assert_eq!(super::normalize("1 2  \n\t\n3\n\n \n"), expected);
+        assert_eq!(super::normalize("\u{feff}1 2\n\n3"), expected);
+        assert_eq!(super::normalize("\n\n"), "");
+        // the indentation is kept, some drawings start with spaces
+        assert_eq!(super::normalize("    [D]    \n"), "    [D]\n");
+
+        let keep_bom = Normalize { strip_bom: false };
+        assert_eq!(keep_bom.apply("\u{feff}1\n"), "\u{feff}1\n");
+    }
+}
@@ -0,0 +1,32 @@
+//! Solutions for advent of code 2022 along with the helpers they share.
+//!
+//! Every day implements [`solution::Solution`] and is listed in [`solution::DAYS`], the
+//! binary only parses the command line and hands it to the [`runner`].
+
+pub mod example;
+pub mod export;
+pub mod grid;
+pub mod input;
+pub mod ocr;
+pub mod parse;
+pub mod runner;
+pub mod search;
+pub mod solution;
+pub mod visualize;
+
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
@@ -1,18 +1,6 @@
-aoc_helper::main! {
-    year: 2022;
-    day01: parse => part_1, part_2;
-    day02: parse => part_1, part_2;
-    day03: parse => part_1, part_2;
-    day04: parse => part_1, part_2;
-    day05: parse => part_1, part_2;
-    day06: parse => part_1, part_2;
-    day07: parse => part_1, part_2;
-    day08: parse => part_1, part_2;
-    day09: parse => part_1, part_2;
-    day10: parse => part_1, part_2;
-    day11: parse => part_1, part_2;
-    day12: parse => part_1, part_2;
-    day13: parse => part_1, part_2;
-    day14: parse => part_1, part_2;
-    day15: parse => part_1, part_2;
+use advent_of_code_2022::{runner, solution};
+
+fn main() -> anyhow::Result<()> {
+    let args = runner::Args::parse(std::env::args().skip(1))?;
+    runner::run(solution::DAYS, &args)
 }
@@ -0,0 +1,77 @@
+use crate::grid::Grid;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+// There's an empty column after each letter
+const GLYPH_SPACING: usize = 1;
+
+// The 4x6 font used by the puzzles that draw letters on a screen
+const FONT: [(char, [&str; GLYPH_HEIGHT]); 17] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn on the screen, unknown letters are replaced by '?'
+pub fn recognize(screen: &Grid<bool>) -> String {
+    let letter_count = (screen.width() + GLYPH_SPACING) / (GLYPH_WIDTH + GLYPH_SPACING);
+    (0..letter_count)
+        .map(|i| {
+            let left = i * (GLYPH_WIDTH + GLYPH_SPACING);
+            FONT.iter()
+                .find(|(_, glyph)| {
+                    glyph.iter().enumerate().all(|(y, row)| {
+                        row.chars().enumerate().all(|(x, c)| {
+                            let lit = screen.get(((left + x) as i32, y as i32)) == Some(&true);
+                            lit == (c == '#')
+                        })
+                    })
+                })
+                .map_or('?', |(letter, _)| *letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::grid::Grid;
+
+    const INPUTS: &str = indoc! {"
+        #..#..###.#..#.####
+        #..#...#..#..#.#...
+        ####...#..#..#.###.
+        #..#...#..#..#.#...
+        #..#...#..#..#.#...
+        #..#..###..##..####
+    "};
+
+    #[test]
+    pub fn recognize() {
+        let screen = Grid::parse(module_path!(), INPUTS, "# or .", |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(super::recognize(&screen), "HIUE");
+
+        let blank = Grid::new(9, 6, false);
+        assert_eq!(super::recognize(&blank), "??");
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+/// Error returned by every `parse` function, it points to the part of the input that was rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: &'static str,
+    /// Starts at 1
+    pub line: usize,
+    /// Starts at 1, counted in chars
+    pub column: usize,
+    pub text: String,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// `text` must be a slice of `input`, it's used to find the line and column of the error.
+    /// `module` is the `module_path!()` of the day, prefer using the `parse_error!` macro.
+    pub fn new(module: &'static str, input: &str, text: &str, expected: impl Into<String>) -> Self {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| input.is_char_boundary(*offset))
+            .unwrap_or(0);
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            day: module.rsplit("::").next().unwrap_or(module),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.lines().next().unwrap_or_default().into(),
+            expected: expected.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: line {}, column {}: expected {}, ",
+            self.day, self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found \"{}\"", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Builds a `ParseError` for the current day, `text` must be a slice of `input`
+#[macro_export]
+macro_rules! parse_error {
+    ($input:expr, $text:expr, $expected:expr) => {
+        $crate::parse::ParseError::new(module_path!(), $input, $text, $expected)
+    };
+}
+
+/// Same as `serde_scan::scan!` but the error points to the line that didn't match the pattern
+#[macro_export]
+macro_rules! try_scan {
+    ($input:expr, $pattern:literal <- $line:ident) => {
+        serde_scan::scan!($pattern <- $line)
+            .map_err(|_| $crate::parse_error!($input, $line, concat!("`", $pattern, "`")))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    const INPUTS: &str = indoc! {"
+        1 2
+        3 é4
+    "};
+
+    #[test]
+    pub fn location() {
+        let input = INPUTS;
+        let text = &input[input.find('4').unwrap()..];
+        let err = parse_error!(input, text, "a letter");
+        assert_eq!((err.day, err.line, err.column), ("tests", 2, 4));
+        assert_eq!(err.text, "4");
+        assert_eq!(
+            err.to_string(),
+            "tests: line 2, column 4: expected a letter, found \"4\""
+        );
+    }
+
+    #[test]
+    pub fn scan() {
+        let input = INPUTS;
+        let line = input.lines().nth(1).unwrap();
+        let result: Result<(usize, usize), _> = try_scan!(input, "{} {}" <- line);
+        let err = result.unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.text, "3 é4");
+        assert_eq!(err.expected, "`{} {}`");
+    }
+}
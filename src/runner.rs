@@ -0,0 +1,264 @@
+use std::{
+    io::Read,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context};
+
+use crate::{
+    example, input,
+    solution::{Answer, Day, Params},
+};
+
+pub const YEAR: u32 = 2022;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    File(PathBuf),
+    Stdin,
+}
+
+/// `cargo run -- [--day n] [--part 1|2] [--input <path|->] [--example]` followed by the options
+/// of the days, unknown arguments are rejected
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Args {
+    pub day: Option<u8>,
+    pub part: Option<u8>,
+    pub input: Option<Input>,
+    pub example: bool,
+    pub options: Options,
+}
+
+/// What the days show or save besides their answers, passed to `Solution::extras`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// `--tree` prints the file system of day 7
+    pub tree: bool,
+    /// `--visualize <day>` plays the day in the terminal at `--fps <n>`
+    pub visualize: Option<u8>,
+    pub fps: u32,
+    /// `--export <day>` saves its pictures in `--out <dir>`, `--scale <n>` pixels per cell.
+    /// `--every <n>` keeps one step out of n in the GIFs instead of the day's default.
+    pub export: Option<u8>,
+    pub out: PathBuf,
+    pub scale: usize,
+    pub every: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            tree: false,
+            visualize: None,
+            fps: 30,
+            export: None,
+            out: "renders".into(),
+            scale: 4,
+            every: None,
+        }
+    }
+}
+
+impl Options {
+    /// True when `day` was given to `--visualize`, `day` is its name or its `module_path!()`
+    pub fn visualize(&self, day: &str) -> bool {
+        self.visualize.is_some_and(|number| is_day(day, number))
+    }
+
+    /// Same as `visualize` for `--export`
+    pub fn export(&self, day: &str) -> bool {
+        self.export.is_some_and(|number| is_day(day, number))
+    }
+}
+
+fn is_day(day: &str, number: u8) -> bool {
+    day.rsplit("::").next() == Some(&format!("day{number:02}"))
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+        let options = &mut parsed.options;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().with_context(|| format!("{arg} needs a value"));
+            match arg.as_str() {
+                "--day" => parsed.day = Some(day_number(&arg, &value()?)?),
+                "--part" => match value()?.as_str() {
+                    "1" => parsed.part = Some(1),
+                    "2" => parsed.part = Some(2),
+                    _ => bail!("--part expects 1 or 2"),
+                },
+                "--input" => {
+                    let path = value()?;
+                    parsed.input = Some(match path.as_str() {
+                        "-" => Input::Stdin,
+                        path => Input::File(path.into()),
+                    });
+                }
+                "--example" => parsed.example = true,
+                "--tree" => options.tree = true,
+                "--visualize" => options.visualize = Some(day_number(&arg, &value()?)?),
+                "--fps" => options.fps = number(&arg, &value()?)?,
+                "--export" => options.export = Some(day_number(&arg, &value()?)?),
+                "--out" => options.out = value()?.into(),
+                "--scale" => options.scale = number(&arg, &value()?)?,
+                "--every" => options.every = Some(number(&arg, &value()?)?),
+                _ => bail!("unknown argument {arg}, see the README for the options"),
+            }
+        }
+        if parsed.input.is_some() && parsed.day.is_none() {
+            bail!("--input needs a --day to know which day to run");
+        }
+        if parsed.input.is_some() && parsed.example {
+            bail!("--input and --example can't be used together");
+        }
+        Ok(parsed)
+    }
+}
+
+// accepts 9, 09 and day09
+fn day_number(flag: &str, day: &str) -> anyhow::Result<u8> {
+    let number = day.strip_prefix("day").unwrap_or(day);
+    match number.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => bail!("{flag} expects a day between 1 and 25, got {day}"),
+    }
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> anyhow::Result<T> {
+    match value.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => bail!("{flag} expects a number, got {value}"),
+    }
+}
+
+pub fn run(days: &[Day], args: &Args) -> anyhow::Result<()> {
+    let selected: Vec<&Day> = match args.day {
+        Some(number) => {
+            let name = format!("day{number:02}");
+            let day = days.iter().find(|day| day.name == name);
+            vec![day.with_context(|| format!("{name} isn't solved yet"))?]
+        }
+        None => days.iter().collect(),
+    };
+    for day in selected {
+        let mut params: [Params; 2] = Default::default();
+        let input = match &args.input {
+            Some(Input::Stdin) => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .context("Failed to read the input from stdin")?;
+                input::normalize(&input)
+            }
+            Some(Input::File(path)) => input::read(path)?,
+            None => {
+                let path = match args.example {
+                    true => example::path(day.name),
+                    false => input_path(day.name),
+                };
+                // running everything skips the days without an input
+                if args.day.is_none() && !path.exists() {
+                    eprintln!("{}: skipped, {} not found", day.name, path.display());
+                    continue;
+                }
+                if args.example {
+                    // the other examples are only checked by the tests
+                    let example = example::read(day.name)?.into_iter().next();
+                    let example =
+                        example.with_context(|| format!("{} is empty", path.display()))?;
+                    params = example.parts.map(|part| part.params);
+                    example.input
+                } else {
+                    input::read(&path)?
+                }
+            }
+        };
+        solve(day, &input, args, &params)?;
+    }
+    Ok(())
+}
+
+fn input_path(day: &str) -> PathBuf {
+    PathBuf::from(format!("inputs/{YEAR}/{}.txt", &day[3..]))
+}
+
+/// Parses the input, solves the requested parts and prints the answers with their timings
+pub fn solve(day: &Day, input: &str, args: &Args, params: &[Params; 2]) -> anyhow::Result<()> {
+    let (data, elapsed) = timed(|| (day.parse)(input));
+    let data = data.context("Failed to parse input")?;
+    println!("{} parse ({elapsed:.1?})", day.name);
+    (day.extras)(&*data, &args.options);
+    let parts = [(1, day.part_1), (2, day.part_2)];
+    for ((number, solve_part), params) in parts.into_iter().zip(params) {
+        if args.part.is_some_and(|part| part != number) {
+            continue;
+        }
+        let (answer, elapsed) = timed(|| solve_part(&*data, params));
+        let answer = answer.with_context(|| format!("{} part_{number} failed", day.name))?;
+        match answer {
+            // drawn below the timing so it isn't shifted
+            Answer::Lines(_) => println!("{} part_{number}: ({elapsed:.1?})\n{answer}", day.name),
+            answer => println!("{} part_{number}: {answer} ({elapsed:.1?})", day.name),
+        }
+    }
+    Ok(())
+}
+
+fn timed<R>(f: impl FnOnce() -> R) -> (R, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Args, Input, Options};
+
+    fn parse(args: &str) -> anyhow::Result<Args> {
+        Args::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    pub fn args() {
+        assert_eq!(parse("").unwrap(), Args::default());
+        let args = parse("--day 9 --part 2 --input - --visualize day09").unwrap();
+        assert_eq!(args.day, Some(9));
+        assert_eq!(args.part, Some(2));
+        assert_eq!(args.input, Some(Input::Stdin));
+        assert_eq!(parse("--day day07 --example").unwrap().day, Some(7));
+        assert_eq!(
+            parse("--day 07 --input other.txt").unwrap().input,
+            Some(Input::File("other.txt".into()))
+        );
+
+        assert!(parse("--day 26").is_err());
+        assert!(parse("--part 3").is_err());
+        assert!(parse("--input -").is_err());
+        assert!(parse("--day 1 --input - --example").is_err());
+        assert!(parse("--dya 3").is_err());
+        assert!(parse("--day").is_err());
+    }
+
+    #[test]
+    pub fn options() {
+        let args = parse("--tree --visualize 9 --fps 60 --export day14 --every 5").unwrap();
+        let options = args.options;
+        assert!(options.tree);
+        assert!(options.visualize("day09") && !options.visualize("day14"));
+        assert!(options.export("advent_of_code_2022::day14"));
+        assert_eq!(
+            (options.fps, options.scale, options.every),
+            (60, 4, Some(5))
+        );
+        assert_eq!(options.out, PathBuf::from("renders"));
+        assert_eq!(Options::default(), parse("").unwrap().options);
+
+        assert!(parse("--fps fast").is_err());
+        assert!(parse("--export day30").is_err());
+    }
+}
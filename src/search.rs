@@ -0,0 +1,225 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+use hashbrown::{hash_map::Entry, HashMap};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N> {
+    /// Goes from the start to the goal, both included
+    pub nodes: Vec<N>,
+    pub cost: usize,
+}
+
+/// Shortest path when every step costs 1.
+/// All the starts are searched at the same time, the path begins at the closest one.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if parents.insert(start.clone(), None).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            let nodes = reconstruct(&parents, node);
+            return Some(Path { nodes, cost });
+        }
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = parents.entry(next.clone()) {
+                entry.insert(Some(node.clone()));
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Shortest path when steps have different costs, `neighbours` returns the cost of each step
+pub fn dijkstra<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    astar(starts, neighbours, |_| 0, is_goal)
+}
+
+/// Same as `dijkstra` but guided by the `heuristic`.
+/// The heuristic must never overestimate the remaining cost or the path might not be the shortest.
+pub fn astar<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> usize,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    // (cost, parent) of the best known path to each node
+    let mut best: HashMap<N, (usize, Option<N>)> = HashMap::new();
+    // The heap only stores indices in `nodes` so N doesn't need to be Ord
+    let mut nodes = vec![];
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if best.insert(start.clone(), (0, None)).is_none() {
+            heap.push(Reverse((heuristic(&start), 0, nodes.len())));
+            nodes.push(start);
+        }
+    }
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let node = nodes[index].clone();
+        if cost > best[&node].0 {
+            // a shorter path to this node was already visited
+            continue;
+        }
+        if is_goal(&node) {
+            let parents = best
+                .into_iter()
+                .map(|(node, (_, parent))| (node, parent))
+                .collect();
+            let nodes = reconstruct(&parents, node);
+            return Some(Path { nodes, cost });
+        }
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            match best.entry(next.clone()) {
+                Entry::Occupied(entry) if entry.get().0 <= next_cost => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert((next_cost, Some(node.clone())));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((next_cost, Some(node.clone())));
+                }
+            }
+            heap.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                nodes.len(),
+            )));
+            nodes.push(next);
+        }
+    }
+    None
+}
+
+/// Every node that can be reached from the starts with the number of steps to get there
+pub fn reachable<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut steps = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if steps.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        let next_steps = steps[&node] + 1;
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = steps.entry(next.clone()) {
+                entry.insert(next_steps);
+                queue.push_back(next);
+            }
+        }
+    }
+    steps
+}
+
+fn reconstruct<N: Eq + Hash + Clone>(parents: &HashMap<N, Option<N>>, end: N) -> Vec<N> {
+    let mut nodes = vec![end];
+    while let Some(Some(parent)) = parents.get(nodes.last().unwrap()) {
+        nodes.push(parent.clone());
+    }
+    nodes.reverse();
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::grid::{Grid, Point};
+
+    // 1 to 9 is the cost of entering a cell, # is a wall
+    const INPUTS: &str = indoc! {"
+        1191
+        1#91
+        1111
+    "};
+
+    fn parse() -> Grid<Option<usize>> {
+        Grid::parse(module_path!(), INPUTS, "a cost or #", |c| match c {
+            '#' => Some(None),
+            c => c.to_digit(10).map(|cost| Some(cost as usize)),
+        })
+        .unwrap()
+    }
+
+    fn open(grid: &Grid<Option<usize>>, point: Point) -> impl Iterator<Item = (Point, usize)> + '_ {
+        grid.neighbours_4(point)
+            .flat_map(|(n, cost)| cost.map(|cost| (n, cost)))
+    }
+
+    #[test]
+    pub fn bfs() {
+        let grid = parse();
+        let path = super::bfs(
+            [(0, 0)],
+            |p| open(&grid, *p).map(|(n, _)| n),
+            |p| *p == (3, 0),
+        );
+        let path = path.unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.nodes, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+
+        let unreachable = super::bfs(
+            [(0, 0)],
+            |p| open(&grid, *p).map(|(n, _)| n),
+            |p| *p == (1, 1),
+        );
+        assert_eq!(unreachable, None);
+    }
+
+    #[test]
+    pub fn dijkstra() {
+        let grid = parse();
+        let path = super::dijkstra([(0, 0)], |p| open(&grid, *p), |p| *p == (3, 0)).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes.len(), 8);
+
+        let manhattan = |(x, y): &Point| ((3 - x).abs() + y.abs()) as usize;
+        let astar = super::astar([(0, 0)], |p| open(&grid, *p), manhattan, |p| *p == (3, 0));
+        assert_eq!(astar.unwrap().cost, path.cost);
+    }
+
+    #[test]
+    pub fn reachable() {
+        let grid = parse();
+        let steps = super::reachable([(0, 0), (3, 0)], |p| open(&grid, *p).map(|(n, _)| n));
+        assert_eq!(steps.len(), 11);
+        assert_eq!(steps[&(2, 0)], 1);
+        assert_eq!(steps[&(1, 2)], 3);
+        assert!(!steps.contains_key(&(1, 1)));
+    }
+}
@@ -0,0 +1,182 @@
+use std::{any::Any, fmt};
+
+use hashbrown::HashMap;
+
+use crate::{parse::ParseError, runner::Options};
+
+/// The answer of a part, most puzzles want a number
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// Drawn on several lines, when it can't be read as text
+    Lines(Vec<String>),
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Int(value as i64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Int(value as i64)
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.into())
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{value}"),
+            Answer::Text(text) => write!(f, "{text}"),
+            Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+        }
+    }
+}
+
+/// Constants of a puzzle replaced for a run, the examples often use smaller ones than the input
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(HashMap<String, i64>);
+
+impl Params {
+    /// The value given for `name`, or `default` which is the one used by the real input.
+    /// Fails when the value doesn't fit in `T`.
+    pub fn get<T: TryFrom<i64>>(&self, name: &str, default: T) -> anyhow::Result<T> {
+        match self.0.get(name) {
+            Some(value) => T::try_from(*value)
+                .map_err(|_| anyhow::anyhow!("the parameter {name} = {value} is out of range")),
+            None => Ok(default),
+        }
+    }
+}
+
+impl FromIterator<(String, i64)> for Params {
+    fn from_iter<I: IntoIterator<Item = (String, i64)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Called by the runner after parsing, for the days that print or export something when
+    /// asked on the command line
+    fn extras(_input: &Self::Input, _options: &Options) {}
+
+    /// Fails when the input parsed but has no answer, like a puzzle with no solution
+    fn part_1(input: &Self::Input) -> anyhow::Result<Answer>;
+
+    fn part_2(input: &Self::Input) -> anyhow::Result<Answer>;
+
+    /// `part_1` with some of its constants replaced, only the days that have some override it
+    fn part_1_with(input: &Self::Input, _params: &Params) -> anyhow::Result<Answer> {
+        Self::part_1(input)
+    }
+
+    fn part_2_with(input: &Self::Input, _params: &Params) -> anyhow::Result<Answer> {
+        Self::part_2(input)
+    }
+}
+
+/// A `Solution` behind function pointers so every day has the same type, the parsed input is
+/// passed around as `Any`
+pub struct Day {
+    pub name: &'static str,
+    pub parse: fn(&str) -> Result<Box<dyn Any>, ParseError>,
+    pub extras: fn(&dyn Any, &Options),
+    pub part_1: fn(&dyn Any, &Params) -> anyhow::Result<Answer>,
+    pub part_2: fn(&dyn Any, &Params) -> anyhow::Result<Answer>,
+}
+
+impl Day {
+    pub const fn new<S: Solution>(name: &'static str) -> Self
+    where
+        S::Input: 'static,
+    {
+        Self {
+            name,
+            parse: |input| Ok(Box::new(S::parse(input)?)),
+            extras: |input, options| S::extras(downcast::<S>(input), options),
+            part_1: |input, params| S::part_1_with(downcast::<S>(input), params),
+            part_2: |input, params| S::part_2_with(downcast::<S>(input), params),
+        }
+    }
+}
+
+fn downcast<S: Solution>(input: &dyn Any) -> &S::Input
+where
+    S::Input: 'static,
+{
+    input
+        .downcast_ref()
+        .expect("the input was parsed by another day")
+}
+
+macro_rules! days {
+    ($($day:ident: $solution:ident),+ $(,)?) => {
+        /// Every day solved so far, in order
+        pub const DAYS: &[Day] = &[$(Day::new::<crate::$day::$solution>(stringify!($day))),+];
+    };
+}
+
+days! {
+    day01: Day01, day02: Day02, day03: Day03, day04: Day04,
+    day05: Day05, day06: Day06, day07: Day07, day08: Day08,
+    day09: Day09, day10: Day10, day11: Day11, day12: Day12,
+    day13: Day13, day14: Day14, day15: Day15, day16: Day16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Answer, Params, DAYS};
+
+    #[test]
+    pub fn registry() {
+        let names: Vec<_> = DAYS.iter().map(|day| day.name).collect();
+        assert_eq!(names.len(), 16);
+        assert!(names.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let day01 = &DAYS[0];
+        let input = (day01.parse)("1000\n2000\n\n3000\n").unwrap();
+        let params = Params::default();
+        assert_eq!((day01.part_1)(&*input, &params).unwrap(), Answer::Int(3000));
+        assert!((day01.parse)("x\n").is_err());
+    }
+
+    #[test]
+    pub fn display() {
+        assert_eq!(Answer::from(12usize).to_string(), "12");
+        assert_eq!(Answer::from("CMZ").to_string(), "CMZ");
+        let lines = Answer::Lines(vec!["#.".into(), ".#".into()]);
+        assert_eq!(lines.to_string(), "#.\n.#");
+    }
+
+    #[test]
+    pub fn params() {
+        let params: Params = [("row".to_string(), 3_000_000_000)].into_iter().collect();
+        assert_eq!(params.get("row", 10i64).unwrap(), 3_000_000_000);
+        assert!(params.get("row", 10i32).is_err());
+        assert_eq!(params.get("max", 20i32).unwrap(), 20);
+    }
+}
@@ -0,0 +1,160 @@
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    thread,
+    time::{Duration, Instant},
+};
+
+use colored::{Color, Colorize};
+
+use crate::{
+    grid::{Grid, Point},
+    runner::Options,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pixel {
+    pub symbol: char,
+    pub color: Color,
+}
+
+impl Pixel {
+    pub fn new(symbol: char, color: Color) -> Self {
+        Self { symbol, color }
+    }
+}
+
+/// Something that can be drawn in the terminal, only the non empty pixels are returned
+pub trait Frame {
+    fn pixels(&self) -> Vec<(Point, Pixel)>;
+}
+
+impl Frame for Vec<(Point, Pixel)> {
+    fn pixels(&self) -> Vec<(Point, Pixel)> {
+        self.clone()
+    }
+}
+
+/// How a grid cell is drawn, None leaves it empty
+pub trait ToPixel {
+    fn to_pixel(&self) -> Option<Pixel>;
+}
+
+impl<T: ToPixel> Frame for Grid<T> {
+    fn pixels(&self) -> Vec<(Point, Pixel)> {
+        self.iter()
+            .filter_map(|(point, cell)| cell.to_pixel().map(|pixel| (point, pixel)))
+            .collect()
+    }
+}
+
+/// Shows frames one after the other in the terminal.
+/// The viewport is cropped around the pixels and only grows so the picture doesn't jump around.
+pub struct Player {
+    frame_time: Duration,
+    colors: bool,
+    // (top left, bottom right), both included
+    viewport: Option<(Point, Point)>,
+    last_frame: Option<Instant>,
+}
+
+impl Player {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            frame_time: Duration::from_secs(1) / fps.max(1),
+            colors: true,
+            viewport: None,
+            last_frame: None,
+        }
+    }
+
+    /// Used by `cargo run -- --visualize day14 [--fps 30]`, `day` is the `module_path!()` of the day
+    pub fn from_options(options: &Options, day: &str) -> Option<Self> {
+        options.visualize(day).then(|| Self::new(options.fps))
+    }
+
+    pub fn without_colors(mut self) -> Self {
+        self.colors = false;
+        self
+    }
+
+    /// Clears the terminal, draws the frame and waits until it's time for the next one
+    pub fn show(&mut self, frame: &impl Frame) {
+        let text = self.render(frame);
+        if let Some(last_frame) = self.last_frame {
+            thread::sleep(self.frame_time.saturating_sub(last_frame.elapsed()));
+        }
+        // clear screen and reset cursor at top left
+        let mut stdout = std::io::stdout().lock();
+        let _ = write!(stdout, "\x1B[2J\x1B[1;1H{text}");
+        let _ = stdout.flush();
+        self.last_frame = Some(Instant::now());
+    }
+
+    pub fn render(&mut self, frame: &impl Frame) -> String {
+        let pixels = frame.pixels();
+        for (point, _) in &pixels {
+            self.viewport = Some(match self.viewport {
+                None => (*point, *point),
+                Some((min, max)) => (
+                    (min.0.min(point.0), min.1.min(point.1)),
+                    (max.0.max(point.0), max.1.max(point.1)),
+                ),
+            });
+        }
+        let Some((min, max)) = self.viewport else {
+            return String::new();
+        };
+
+        let width = (max.0 - min.0 + 1) as usize;
+        let height = (max.1 - min.1 + 1) as usize;
+        let mut canvas = Grid::new(width, height, None);
+        for (point, pixel) in pixels {
+            canvas[(point.0 - min.0, point.1 - min.1)] = Some(pixel);
+        }
+
+        let mut text = String::new();
+        for y in 0..height as i32 {
+            let mut line = String::new();
+            for x in 0..width as i32 {
+                match canvas[(x, y)] {
+                    Some(pixel) if self.colors => {
+                        let symbol = pixel.symbol.to_string();
+                        write!(line, "{}", symbol.color(pixel.color)).unwrap();
+                    }
+                    Some(pixel) => line.push(pixel.symbol),
+                    None => line.push(' '),
+                }
+            }
+            text.push_str(line.trim_end());
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use colored::Color;
+    use indoc::indoc;
+
+    use super::{Pixel, Player};
+
+    #[test]
+    pub fn render() {
+        let mut player = Player::new(60).without_colors();
+        let frame = vec![
+            ((10, 5), Pixel::new('#', Color::White)),
+            ((12, 6), Pixel::new('o', Color::Yellow)),
+        ];
+        let expected = indoc! {"
+            #
+              o
+        "};
+        assert_eq!(player.render(&frame), expected);
+
+        // the viewport never shrinks
+        let frame = vec![((11, 4), Pixel::new('+', Color::Red))];
+        assert_eq!(player.render(&frame), " +\n\n\n");
+    }
+}
@@ -0,0 +1,58 @@
+// Runs every day against its input in inputs/2022 and compares both parts with answers/2022.toml.
+// Days without an input are skipped so the suite still passes on a fresh clone without inputs.
+
+use advent_of_code_2022::{
+    input,
+    runner::YEAR,
+    solution::{Answer, Day, Params, DAYS},
+};
+
+#[test]
+fn answers() {
+    let answers = std::fs::read_to_string(format!("answers/{YEAR}.toml")).unwrap();
+    let answers: toml::Value = toml::from_str(&answers).unwrap();
+    let mut errors = vec![];
+    for day in DAYS {
+        check_day(&answers, &mut errors, day);
+    }
+    assert!(errors.is_empty(), "\n{}", errors.join("\n"));
+}
+
+fn check_day(answers: &toml::Value, errors: &mut Vec<String>, day: &Day) {
+    let name = day.name;
+    let path = format!("inputs/{YEAR}/{}.txt", &name[3..]);
+    let Ok(input) = input::read(&path) else {
+        eprintln!("{name}: skipped, {path} not found");
+        return;
+    };
+    let data = match (day.parse)(&input) {
+        Ok(data) => data,
+        Err(err) => {
+            errors.push(err.to_string());
+            return;
+        }
+    };
+    let mut check = |part: &str, result: anyhow::Result<Answer>| {
+        let result = match result {
+            Ok(result) => result.to_string(),
+            Err(err) => {
+                errors.push(format!("{name} {part}: {err:#}"));
+                return;
+            }
+        };
+        let expected = match answers.get(name).and_then(|answers| answers.get(part)) {
+            Some(toml::Value::String(answer)) => answer.clone(),
+            Some(answer) => answer.to_string(),
+            None => {
+                errors.push(format!("{name} {part}: no answer in answers/{YEAR}.toml"));
+                return;
+            }
+        };
+        if result != expected {
+            errors.push(format!("{name} {part}: expected {expected}, got {result}"));
+        }
+    };
+    let params = Params::default();
+    check("part_1", (day.part_1)(&*data, &params));
+    check("part_2", (day.part_2)(&*data, &params));
+}
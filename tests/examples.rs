@@ -0,0 +1,53 @@
+// Runs every example of examples/2022 through its day and checks the answers the puzzle gives.
+// Each file holds one or more `[[example]]` with the input, the answers and the parameters of
+// each part when the example uses other constants than the real input, see src/example.rs.
+
+use advent_of_code_2022::{
+    example,
+    solution::{Day, DAYS},
+};
+
+#[test]
+fn examples() {
+    let mut errors = vec![];
+    for day in DAYS {
+        check_day(&mut errors, day);
+    }
+    assert!(errors.is_empty(), "\n{}", errors.join("\n"));
+}
+
+fn check_day(errors: &mut Vec<String>, day: &Day) {
+    let examples = match example::read(day.name) {
+        Ok(examples) => examples,
+        Err(err) => {
+            errors.push(format!("{}: {err:#}", day.name));
+            return;
+        }
+    };
+    if examples.is_empty() {
+        errors.push(format!("{}: no example", day.name));
+    }
+    for (i, example) in examples.iter().enumerate() {
+        let name = format!("{} example {}", day.name, i + 1);
+        let data = match (day.parse)(&example.input) {
+            Ok(data) => data,
+            Err(err) => {
+                errors.push(format!("{name}: {err}"));
+                continue;
+            }
+        };
+        let parts = [("part_1", day.part_1), ("part_2", day.part_2)];
+        for ((part, solve), expected) in parts.into_iter().zip(&example.parts) {
+            let Some(answer) = &expected.answer else {
+                continue;
+            };
+            match solve(&*data, &expected.params) {
+                Ok(result) if result == *answer => {}
+                Ok(result) => {
+                    errors.push(format!("{name} {part}: expected {answer}, got {result}"))
+                }
+                Err(err) => errors.push(format!("{name} {part}: {err:#}")),
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// Feeds every day its examples from examples/2022 with other line endings and extra whitespace.
+// Once normalized they must give the same answers as the examples themselves.
+
+use advent_of_code_2022::{
+    example::{self, Example},
+    input,
+    solution::{Answer, Day, DAYS},
+};
+
+#[test]
+fn variants() {
+    for day in DAYS {
+        for example in example::read(day.name).unwrap() {
+            check_variants(day, &example);
+        }
+    }
+}
+
+fn check_variants(day: &Day, example: &Example) {
+    let text = &example.input;
+    let expected = answers(day, example, text);
+
+    let crlf = text.replace('\n', "\r\n");
+    let trailing_spaces: String = text.lines().map(|line| format!("{line} \t\n")).collect();
+    let variants = [
+        ("CRLF", crlf.clone()),
+        ("trailing spaces", trailing_spaces),
+        ("trailing blank lines", format!("{text}\n \n\n")),
+        ("no final newline", text.trim_end().into()),
+        ("a BOM", format!("\u{feff}{text}")),
+        (
+            "everything",
+            format!("\u{feff}{}\r\n\r\n", crlf.replace("\r\n", "  \r\n")),
+        ),
+    ];
+    for (variant, raw) in variants {
+        let result = answers(day, example, &input::normalize(&raw));
+        assert_eq!(result, expected, "{} with {variant}", day.name);
+    }
+}
+
+fn answers(day: &Day, example: &Example, input: &str) -> (Answer, Answer) {
+    let data = (day.parse)(input).unwrap_or_else(|err| panic!("{err}"));
+    let [part_1, part_2] = &example.parts;
+    (
+        (day.part_1)(&*data, &part_1.params).unwrap(),
+        (day.part_2)(&*data, &part_2.params).unwrap(),
+    )
+}